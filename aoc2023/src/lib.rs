@@ -0,0 +1,191 @@
+//! Every day's solution behind one API, for tools that want answers without going
+//! through the `runner` binary.
+
+use std::fmt;
+
+pub use common::{Answer, Error, Inspection, Inspector, Part, Sample, Samples, Solution};
+
+/// Every registered day, in day order.
+const SOLUTIONS: &[&dyn Solution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+];
+
+pub fn all() -> impl Iterator<Item = &'static dyn Solution> {
+    SOLUTIONS.iter().copied()
+}
+
+pub fn find(day: u8) -> Option<&'static dyn Solution> {
+    all().find(|solution| solution.day() == day)
+}
+
+/// Why [`solve`] couldn't produce an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownDay(u8),
+    Unsolved {
+        day: u8,
+        part: Part,
+    },
+    /// The solution couldn't make sense of the input
+    Input(Error),
+}
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::UnknownDay(day) => write!(f, "day {} isn't implemented", day),
+            SolveError::Unsolved { day, part } => {
+                write!(f, "day {}, {} isn't solved yet", day, part)
+            }
+            SolveError::Input(error) => write!(f, "{}", error),
+        }
+    }
+}
+impl std::error::Error for SolveError {}
+impl From<Error> for SolveError {
+    fn from(error: Error) -> Self {
+        SolveError::Input(error)
+    }
+}
+
+pub fn solve(day: u8, part: Part, input: &str) -> Result<Answer, SolveError> {
+    let solution = find(day).ok_or(SolveError::UnknownDay(day))?;
+    solution
+        .solve(part, input)
+        .ok_or(SolveError::Unsolved { day, part })?
+        .map_err(SolveError::from)
+}
+
+/// Every structural problem with `input` for `day`, without solving anything.
+pub fn validate(day: u8, input: &str) -> Result<Vec<Error>, SolveError> {
+    Ok(find(day)
+        .ok_or(SolveError::UnknownDay(day))?
+        .validate(input))
+}
+
+/// What's known about a registered day, without running anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayInfo {
+    pub day: u8,
+    pub name: &'static str,
+    pub description: Option<&'static str>,
+    /// The parts that have a solution
+    pub parts: Vec<Part>,
+    pub samples: Vec<&'static str>,
+}
+impl DayInfo {
+    fn of(solution: &dyn Solution) -> DayInfo {
+        DayInfo {
+            day: solution.day(),
+            name: solution.name(),
+            description: solution.description(),
+            parts: Part::BOTH
+                .into_iter()
+                .filter(|&part| solution.is_solved(part))
+                .collect(),
+            samples: solution.samples().names().collect(),
+        }
+    }
+}
+
+/// Every registered day, in day order.
+pub fn days() -> impl Iterator<Item = DayInfo> {
+    all().map(DayInfo::of)
+}
+
+pub fn day(day: u8) -> Option<DayInfo> {
+    find(day).map(DayInfo::of)
+}
+
+#[test]
+fn registered_days_are_unique_and_ordered() {
+    let days = all().map(|s| s.day()).collect::<Vec<_>>();
+    assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+}
+#[test]
+fn every_sample_gives_its_recorded_answers() {
+    for solution in all() {
+        let samples = solution.samples();
+        for name in samples.names() {
+            for part in Part::BOTH {
+                let Some(expected) = samples.expected(name, part) else {
+                    continue;
+                };
+                let answer = solution.solve(part, samples.input(name).unwrap());
+                assert!(
+                    matches!(&answer, Some(Ok(answer)) if *answer == expected),
+                    "day {}, {} on sample {}: expected {}, got {:?}",
+                    solution.day(),
+                    part,
+                    name,
+                    expected,
+                    answer
+                );
+            }
+        }
+    }
+}
+#[test]
+fn solve_reports_why_there_is_no_answer() {
+    assert_eq!(
+        solve(6, Part::First, "Time: 7\nDistance: 9\n"),
+        Ok(Answer::from(4u64))
+    );
+    assert_eq!(solve(26, Part::First, ""), Err(SolveError::UnknownDay(26)));
+    assert_eq!(
+        solve(10, Part::Second, ""),
+        Err(SolveError::Unsolved {
+            day: 10,
+            part: Part::Second
+        })
+    );
+    assert!(matches!(
+        solve(6, Part::First, "Time: 7\n"),
+        Err(SolveError::Input(_))
+    ));
+}
+#[test]
+fn every_sample_is_valid() {
+    for solution in all() {
+        let samples = solution.samples();
+        for name in samples.names() {
+            let problems = solution.validate(samples.input(name).unwrap());
+            assert!(
+                problems.is_empty(),
+                "day {}, sample {}: {:?}",
+                solution.day(),
+                name,
+                problems
+            );
+        }
+    }
+}
+#[test]
+fn validation_reports_every_problem() {
+    assert_eq!(validate(6, "Time: 7 15\nDistance: 9 40\n"), Ok(vec![]));
+    assert_eq!(validate(26, ""), Err(SolveError::UnknownDay(26)));
+    let problems = validate(7, "32T3 765\nT55J5 x\n").unwrap();
+    assert_eq!(
+        problems
+            .iter()
+            .map(|problem| problem.line)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+}
+#[test]
+fn metadata_lists_solved_parts() {
+    let day10 = day(10).unwrap();
+    assert_eq!(day10.name, "Pipe Maze");
+    assert_eq!(day10.parts, vec![Part::First]);
+    assert_eq!(days().count(), all().count());
+}
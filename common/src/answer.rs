@@ -0,0 +1,122 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// What a part produces.
+///
+/// Integer answers keep the width their solution computed them in,
+/// but compare equal to any other integer of the same value.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    I64(i64),
+    Text(String),
+}
+impl Answer {
+    /// The answer as an integer wide enough to hold every variant, if it is one.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::U32(n) => Some(*n as i128),
+            Answer::U64(n) => Some(*n as i128),
+            Answer::Usize(n) => Some(*n as i128),
+            Answer::I64(n) => Some(*n as i128),
+            Answer::Text(_) => None,
+        }
+    }
+}
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::U32(n) => write!(f, "{}", n),
+            Answer::U64(n) => write!(f, "{}", n),
+            Answer::Usize(n) => write!(f, "{}", n),
+            Answer::I64(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+/// Reads back a displayed answer: anything that parses as an integer is one.
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if let Ok(n) = s.parse::<i64>() {
+            Answer::I64(n)
+        } else if let Ok(n) = s.parse::<u64>() {
+            Answer::U64(n)
+        } else {
+            Answer::Text(s.to_string())
+        })
+    }
+}
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            _ => self.as_integer().is_some() && self.as_integer() == other.as_integer(),
+        }
+    }
+}
+impl Eq for Answer {}
+macro_rules! integer_answers {
+    ($($variant:ident($t:ty)),+) => {
+        $(
+        impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::$variant(n)
+            }
+        }
+        )+
+    };
+}
+integer_answers!(U32(u32), U64(u64), Usize(usize), I64(i64));
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+macro_rules! compare_with_integers {
+    ($($t:ty),+) => {
+        $(
+        impl PartialEq<$t> for Answer {
+            fn eq(&self, other: &$t) -> bool {
+                self.as_integer() == Some(*other as i128)
+            }
+        }
+        )+
+    };
+}
+compare_with_integers!(i32, u32, i64, u64, usize);
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            Answer::Text(s) => s == other,
+            _ => self.as_integer() == other.parse::<i128>().ok(),
+        }
+    }
+}
+
+#[test]
+fn integers_of_different_widths_compare_equal() {
+    assert_eq!(Answer::from(4361usize), Answer::from(4361u64));
+    assert_eq!(Answer::from(-3i64), -3);
+    assert_ne!(Answer::from(4361u32), Answer::from("4361 "));
+    assert_eq!(Answer::from(4361u32), "4361");
+}
+#[test]
+fn displayed_answers_parse_back_to_equal_answers() {
+    for answer in [
+        Answer::from(u64::MAX),
+        Answer::from(-3i64),
+        Answer::from("LRLR"),
+    ] {
+        assert_eq!(answer.to_string().parse::<Answer>().unwrap(), answer);
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Something in a day's input that its solution couldn't make sense of.
+///
+/// Lines and columns are 1-based; columns count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    /// What the solution was looking for where it went wrong
+    pub expected: String,
+    /// The offending line, for showing the error in context
+    pub line_text: String,
+}
+impl Error {
+    /// Points at the start of `span`, which must be a slice of `input`.
+    ///
+    /// Slicing `input[input.len()..]` points just past the end, for input that stops short.
+    pub fn at(day: u8, input: &str, span: &str, expected: impl Into<String>) -> Error {
+        let offset = (span.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        debug_assert!(offset <= input.len(), "span is not a slice of input");
+        Error::at_offset(day, input, offset.min(input.len()), expected)
+    }
+    /// Points at the character starting at byte `offset` into `input`.
+    pub fn at_offset(day: u8, input: &str, offset: usize, expected: impl Into<String>) -> Error {
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Error {
+            day,
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            expected: expected.into(),
+            line_text: input[line_start..line_end].to_string(),
+        }
+    }
+    /// Where each of `problems` is, as (line, column) pairs, for checking what was reported.
+    pub fn positions(problems: &[Error]) -> Vec<(usize, usize)> {
+        problems
+            .iter()
+            .map(|problem| (problem.line, problem.column))
+            .collect()
+    }
+    /// The offending line with a caret under the column at fault, e.g.
+    ///
+    /// ```text
+    ///   3 | Game 3: 8 purple
+    ///     |           ^ expected a color
+    /// ```
+    pub fn snippet(&self) -> String {
+        let gutter = self.line.to_string().len();
+        format!(
+            " {:>gutter$} | {}\n {:>gutter$} | {}^ expected {}",
+            self.line,
+            self.line_text,
+            "",
+            " ".repeat(self.column - 1),
+            self.expected,
+        )
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}: line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+impl std::error::Error for Error {}
+
+/// Parses `span`, a slice of `input`, reporting where it went wrong if it doesn't parse.
+pub fn parse_at<T: FromStr>(
+    day: u8,
+    input: &str,
+    span: &str,
+    expected: impl Into<String>,
+) -> Result<T, Error> {
+    span.parse()
+        .map_err(|_| Error::at(day, input, span, expected))
+}
+
+#[test]
+fn error_points_at_line_and_column() {
+    let input = "Game 1: 3 blue\nGame 2: 8 purple\n";
+    let span = &input[25..];
+    let error = Error::at(2, input, span, "a color");
+    assert_eq!((error.line, error.column), (2, 11));
+    assert_eq!(error.line_text, "Game 2: 8 purple");
+    assert_eq!(
+        error.snippet(),
+        " 2 | Game 2: 8 purple\n   |           ^ expected a color"
+    );
+}
+#[test]
+fn error_can_point_past_the_end() {
+    let input = "LR\n";
+    let error = Error::at(8, input, &input[input.len()..], "a blank line");
+    assert_eq!((error.line, error.column), (2, 1));
+    assert_eq!(error.line_text, "");
+}
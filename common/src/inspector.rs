@@ -0,0 +1,33 @@
+/// What an inspector found, or why it couldn't look.
+pub type Inspection = Result<String, Box<dyn std::error::Error>>;
+
+/// A day-specific command for poking at what its solution makes of an input,
+/// run from the runner's REPL.
+pub struct Inspector {
+    /// What to type to run it, e.g. `map seed`; any further words are passed on as arguments
+    pub command: &'static str,
+    /// The arguments it takes, e.g. `<seed>`, for `help`
+    pub args: &'static str,
+    /// What it does, for `help`
+    pub help: &'static str,
+    pub run: fn(input: &str, args: &[&str]) -> Inspection,
+}
+impl Inspector {
+    /// The arguments following this inspector's command in `words`, if it's this one.
+    pub fn matches<'a, 'w>(&self, words: &'a [&'w str]) -> Option<&'a [&'w str]> {
+        let command = self.command.split_whitespace().collect::<Vec<_>>();
+        words.starts_with(&command).then(|| &words[command.len()..])
+    }
+}
+
+#[test]
+fn command_words_are_matched_before_the_arguments() {
+    let inspector = Inspector {
+        command: "map seed",
+        args: "<seed>",
+        help: "",
+        run: |_, _| Ok(String::new()),
+    };
+    assert_eq!(inspector.matches(&["map", "seed", "79"]), Some(&["79"][..]));
+    assert_eq!(inspector.matches(&["map"]), None);
+}
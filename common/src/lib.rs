@@ -0,0 +1,84 @@
+use std::fmt;
+
+mod answer;
+mod error;
+mod inspector;
+mod sample;
+mod validate;
+pub use answer::Answer;
+pub use error::{parse_at, Error};
+pub use inspector::{Inspection, Inspector};
+pub use sample::{Sample, Samples};
+pub use validate::grid_problems;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    First,
+    Second,
+}
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::First, Part::Second];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::First => 1,
+            Part::Second => 2,
+        }
+    }
+}
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "part {}", self.number())
+    }
+}
+
+/// A single day's puzzle, as seen by the runner and other tools.
+///
+/// Every `dayN` crate exposes a unit struct implementing this, which `aoc2023` registers
+/// in its `SOLUTIONS` and hands out through `aoc2023::all` and `aoc2023::find`.
+pub trait Solution: Sync {
+    fn day(&self) -> u8;
+    /// The puzzle's title, as shown on its page.
+    fn name(&self) -> &'static str;
+    /// Anything worth knowing about the solution that doesn't fit in its name.
+    fn description(&self) -> Option<&'static str> {
+        None
+    }
+    /// Days that haven't cracked a part yet override this, and return `None` from the
+    /// corresponding `partN` method; callers can then tell without any input to hand.
+    fn is_solved(&self, _part: Part) -> bool {
+        true
+    }
+    /// The examples from the puzzle text, for trying out a solution.
+    fn samples(&self) -> &'static Samples {
+        &Samples::NONE
+    }
+    /// Commands for exploring how this day parses its input, beyond running the parts.
+    fn inspectors(&self) -> &'static [Inspector] {
+        &[]
+    }
+    /// Checks the input's overall shape without solving anything, reporting every problem
+    /// rather than stopping at the first.
+    fn validate(&self, _input: &str) -> Vec<Error> {
+        Vec::new()
+    }
+    /// A line by line account of how `part` reads the input, for days that can give one.
+    fn explain(&self, _part: Part, _input: &str) -> Option<String> {
+        None
+    }
+    /// The answer to part 1, or `None` if it isn't solved yet.
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>>;
+    /// The answer to part 2, or `None` if it isn't solved yet.
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>>;
+
+    /// Runs `part` on `input`, or returns `None` if it isn't solved yet.
+    fn solve(&self, part: Part, input: &str) -> Option<Result<Answer, Error>> {
+        if !self.is_solved(part) {
+            return None;
+        }
+        match part {
+            Part::First => self.part1(input),
+            Part::Second => self.part2(input),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use toml::{Table, Value};
+
+use crate::{Answer, Error, Part};
+
+/// An example input from a day's puzzle text, kept in `dayN/samples/NAME.txt`.
+#[derive(Debug)]
+pub struct Sample {
+    pub name: &'static str,
+    pub input: &'static str,
+}
+
+/// A day's samples, along with the answers the puzzle text gives for them.
+///
+/// Those live next to the samples in `samples/answers.toml`, keyed by sample name:
+///
+/// ```toml
+/// [example]
+/// part1 = 6440
+/// part2 = 5905
+/// ```
+///
+/// Build this with [`samples!`](crate::samples), which embeds the files at compile time.
+#[derive(Debug)]
+pub struct Samples {
+    pub samples: &'static [Sample],
+    pub answers: &'static str,
+}
+impl Samples {
+    pub const NONE: Samples = Samples {
+        samples: &[],
+        answers: "",
+    };
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        self.samples.iter().map(|sample| sample.name)
+    }
+    pub fn input(&self, name: &str) -> Option<&'static str> {
+        self.samples
+            .iter()
+            .find(|sample| sample.name == name)
+            .map(|sample| sample.input)
+    }
+    /// What `part` should give for the named sample, if the puzzle says.
+    pub fn expected(&self, name: &str, part: Part) -> Option<Answer> {
+        let answers = self
+            .answers
+            .parse::<Table>()
+            .expect("samples/answers.toml should be valid TOML");
+        let key = match part {
+            Part::First => "part1",
+            Part::Second => "part2",
+        };
+        match answers.get(name)?.get(key)? {
+            Value::Integer(n) => Some(Answer::from(*n)),
+            Value::String(s) => s.parse().ok(),
+            other => other.to_string().parse().ok(),
+        }
+    }
+    /// Asserts that `solve` gives the recorded answer for the named sample; for tests.
+    pub fn check(&self, name: &str, part: Part, solve: impl Fn(&str) -> Result<Answer, Error>) {
+        let input = self
+            .input(name)
+            .unwrap_or_else(|| panic!("there's no sample named '{}'", name));
+        let expected = self
+            .expected(name, part)
+            .unwrap_or_else(|| panic!("no {} answer is recorded for sample '{}'", part, name));
+        match solve(input) {
+            Ok(answer) => assert_eq!(answer, expected, "{} on sample '{}'", part, name),
+            Err(error) => panic!("{} on sample '{}':\n{}", part, name, error.snippet()),
+        }
+    }
+}
+
+/// Embeds the named `samples/NAME.txt` files and `samples/answers.toml` of the calling crate.
+#[macro_export]
+macro_rules! samples {
+    ($($name:literal),* $(,)?) => {
+        $crate::Samples {
+            samples: &[$($crate::Sample {
+                name: $name,
+                input: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/samples/", $name, ".txt")),
+            }),*],
+            answers: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/samples/answers.toml")),
+        }
+    };
+}
+
+#[test]
+fn expected_answers_are_looked_up_by_sample_and_part() {
+    let samples = Samples {
+        samples: &[Sample {
+            name: "example",
+            input: "1\n",
+        }],
+        answers: "[example]\npart1 = 142\npart2 = \"ZZZ\"\n",
+    };
+    assert_eq!(samples.input("example"), Some("1\n"));
+    assert_eq!(
+        samples.expected("example", Part::First),
+        Some(142u64.into())
+    );
+    assert_eq!(
+        samples.expected("example", Part::Second),
+        Some("ZZZ".into())
+    );
+    assert_eq!(samples.expected("other", Part::First), None);
+}
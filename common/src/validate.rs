@@ -0,0 +1,49 @@
+use crate::Error;
+
+/// Every way `input` falls short of being a rectangular grid of cells accepted by `is_cell`:
+/// rows of a different width from the first, and cells that aren't `expected`.
+///
+/// Rows end at '\n' alone, as the grid days split them, so a '\r' is a cell like any other.
+pub fn grid_problems(
+    day: u8,
+    input: &str,
+    is_cell: impl Fn(char) -> bool,
+    expected: &str,
+) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let rows = input.split('\n').filter(|line| !line.is_empty());
+    let Some(width) = rows.clone().next().map(|row| row.chars().count()) else {
+        return vec![Error::at(day, input, input, "at least one row")];
+    };
+    for row in rows {
+        for (i, c) in row.char_indices() {
+            if !is_cell(c) {
+                problems.push(Error::at(day, input, &row[i..], expected));
+            }
+        }
+        let row_width = row.chars().count();
+        if row_width != width {
+            problems.push(Error::at(
+                day,
+                input,
+                row,
+                format!(
+                    "a row {} cells wide like the first, not {}",
+                    width, row_width
+                ),
+            ));
+        }
+    }
+    problems
+}
+
+#[test]
+fn ragged_rows_and_stray_cells_are_all_reported() {
+    let problems = grid_problems(3, "..#\n.x\n...\n", |c| ".#".contains(c), "'.' or '#'");
+    assert_eq!(Error::positions(&problems), vec![(2, 2), (2, 1)]);
+}
+#[test]
+fn carriage_returns_are_cells() {
+    let problems = grid_problems(11, "..#\r\n...\r\n", |c| ".#".contains(c), "'.' or '#'");
+    assert_eq!(Error::positions(&problems), vec![(1, 4), (2, 4)]);
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use crate::{Scanner, Token};
+
+/// How one line of a calibration document was read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReading<'a> {
+    /// 1-based, counting blank lines too
+    pub number: usize,
+    pub text: &'a str,
+    /// The first and last digits chosen, or `None` when the line has no digits at all
+    pub digits: Option<(Token, Token)>,
+}
+impl LineReading<'_> {
+    /// What the line adds to the total.
+    pub fn value(&self) -> Option<u32> {
+        self.digits
+            .map(|(first, last)| first.value * 10 + last.value)
+    }
+    fn describe(&self, token: Token) -> String {
+        format!(
+            "'{}' ({}) at byte {}",
+            &self.text[token.offset..token.offset + token.len],
+            if token.spelled { "spelled" } else { "numeral" },
+            token.offset
+        )
+    }
+}
+
+/// Every line's reading, including the lines that couldn't be read, which `part1` and
+/// `part2` would stop at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    pub lines: Vec<LineReading<'a>>,
+}
+impl Explanation<'_> {
+    /// The sum of every line that has digits.
+    pub fn total(&self) -> u64 {
+        self.lines
+            .iter()
+            .filter_map(LineReading::value)
+            .map(u64::from)
+            .sum()
+    }
+    pub fn unreadable(&self) -> impl Iterator<Item = &LineReading<'_>> {
+        self.lines.iter().filter(|line| line.digits.is_none())
+    }
+}
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines.iter() {
+            write!(f, "line {}: {}", line.number, line.text)?;
+            match line.digits {
+                Some((first, last)) => writeln!(
+                    f,
+                    "\n  first {}, last {}: adds {}",
+                    line.describe(first),
+                    line.describe(last),
+                    first.value * 10 + last.value
+                )?,
+                None => writeln!(f, "\n  no digits, so it adds nothing")?,
+            }
+        }
+        write!(f, "total: {}", self.total())?;
+        match self.unreadable().count() {
+            0 => Ok(()),
+            1 => write!(f, " (1 line had no digits)"),
+            n => write!(f, " ({} lines had no digits)", n),
+        }
+    }
+}
+
+/// Reads every non-blank line of `input` with `scanner`, carrying on past lines without
+/// digits.
+pub fn explain<'a>(input: &'a str, scanner: &Scanner) -> Explanation<'a> {
+    Explanation {
+        lines: input
+            .split('\n')
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, text)| LineReading {
+                number: i + 1,
+                text,
+                digits: scanner.first_and_last(text),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn every_line_is_explained() {
+    let explanation = explain(
+        "two1nine\nabc\n\nxtwone3four\n",
+        &crate::DigitVocabulary::english().scanner(),
+    );
+    assert_eq!(
+        explanation
+            .lines
+            .iter()
+            .map(|line| (line.number, line.value()))
+            .collect::<Vec<_>>(),
+        vec![(1, Some(29)), (2, None), (4, Some(24))]
+    );
+    assert_eq!(explanation.total(), 53);
+    assert_eq!(
+        explanation.to_string(),
+        "line 1: two1nine\n  first 'two' (spelled) at byte 0, last 'nine' (spelled) at byte 4: adds 29\n\
+         line 2: abc\n  no digits, so it adds nothing\n\
+         line 4: xtwone3four\n  first 'two' (spelled) at byte 1, last 'four' (spelled) at byte 7: adds 24\n\
+         total: 53 (1 line had no digits)"
+    );
+}
@@ -1,48 +1,80 @@
+use common::{samples, Answer, Error, Part, Samples, Solution};
+pub use explain::{explain, Explanation, LineReading};
+pub use scanner::{Scanner, Token};
+use std::io::BufRead;
+pub use stream::{calibrate, RunningTotal, RunningTotals, StreamError};
+pub use vocabulary::DigitVocabulary;
+
+mod explain;
+mod scanner;
+mod stream;
+mod vocabulary;
+
+const DAY: u8 = 1;
+const SAMPLES: Samples = samples!("example", "spelled_out");
+
+#[cfg(test)]
 const DIGITS: [char; 10] = ['1', '2', '3', '4', '5', '6', '7', '8', '9', '0'];
 const DIGIT_NAMES: [&str; 9] = [
     "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
 ];
 
-pub fn part1(input: &str) -> String {
-    format!(
-        "{}",
-        input
-            .split('\n')
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                find_first_digit_value_with_index(line).unwrap().1 * 10
-                    + find_last_digit_value_with_index(line).unwrap().1
-            })
-            .sum::<usize>()
-    )
+const NO_NUMERAL: &str = "a digit somewhere on the line";
+const NO_DIGIT: &str = "a digit or a digit's name somewhere on the line";
+
+/// Sums each line's first and last digit, read as a two digit number.
+fn calibration_sum(input: &str, scanner: &Scanner, expected: &str) -> Result<Answer, Error> {
+    input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (first, last) = scanner
+                .first_and_last(line)
+                .ok_or_else(|| Error::at(DAY, input, line, expected))?;
+            Ok(u64::from(first.value * 10 + last.value))
+        })
+        .sum::<Result<u64, Error>>()
+        .map(Answer::from)
+}
+
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    calibration_sum(input, &Scanner::numerals(), NO_NUMERAL)
 }
 #[test]
 fn part1_on_sample_input() {
-    let input = "1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet";
-    assert_eq!(part1(input), format!("{}", 12 + 38 + 15 + 77));
+    SAMPLES.check("example", Part::First, part1);
 }
 
+#[cfg(test)]
 fn find_first_digit_value_with_index(line: &str) -> Option<(usize, usize)> {
     line.find(|c| DIGITS.contains(&c)).map(|line_index| {
         (
             line_index,
-            line.chars().nth(line_index).unwrap().to_digit(10).unwrap() as usize,
+            line[line_index..]
+                .chars()
+                .next()
+                .and_then(|c| c.to_digit(10))
+                .unwrap() as usize,
         )
     })
 }
 #[test]
 fn test_find_first_digit_value_with_index() {
     let line = "two1nine";
-    assert_eq!(Some((3, 1)), find_first_digit_value_with_index(line))
+    assert_eq!(Some((3, 1)), find_first_digit_value_with_index(line));
+    // offsets are in bytes, so wider characters before the digit mustn't throw it off
+    assert_eq!(Some((3, 5)), find_first_digit_value_with_index("zé5ro"));
 }
+#[cfg(test)]
 fn find_last_digit_value_with_index(line: &str) -> Option<(usize, usize)> {
     line.rfind(|c| DIGITS.contains(&c)).map(|line_index| {
         (
             line_index,
-            line.chars().nth(line_index).unwrap().to_digit(10).unwrap() as usize,
+            line[line_index..]
+                .chars()
+                .next()
+                .and_then(|c| c.to_digit(10))
+                .unwrap() as usize,
         )
     })
 }
@@ -51,6 +83,7 @@ fn test_find_last_digit_value_with_index() {
     let line = "two1nine";
     assert_eq!(Some((3, 1)), find_last_digit_value_with_index(line))
 }
+#[cfg(test)]
 fn find_first_named_digit_value_with_index(line: &str) -> Option<(usize, usize)> {
     DIGIT_NAMES
         .iter()
@@ -66,6 +99,7 @@ fn test_find_first_named_digit_with_index() {
     let line = "two1nine";
     assert_eq!(Some((0, 2)), find_first_named_digit_value_with_index(line))
 }
+#[cfg(test)]
 fn find_last_named_digit_value_with_index(line: &str) -> Option<(usize, usize)> {
     DIGIT_NAMES
         .iter()
@@ -82,61 +116,267 @@ fn test_find_last_named_digit_with_index() {
     assert_eq!(Some((4, 9)), find_last_named_digit_value_with_index(line))
 }
 
-pub fn part2(input: &str) -> String {
-    format!(
-        "{}",
-        input
-            .split('\n')
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                let first = match (
-                    find_first_digit_value_with_index(line),
-                    find_first_named_digit_value_with_index(line),
-                ) {
-                    (None, None) => panic!(),
-                    (Some((_, digit_value)), None) => digit_value,
-                    (None, Some((_, digit_value))) => digit_value,
-                    (Some((d_index, d_value)), Some((n_index, n_value))) => {
-                        if d_index < n_index {
-                            d_value
-                        } else {
-                            n_value
-                        }
+/// Every line needs a calibration value, whether as a digit or spelled out.
+pub fn validate(input: &str) -> Vec<Error> {
+    let scanner = DigitVocabulary::english().scanner();
+    input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .filter(|line| scanner.first_and_last(line).is_none())
+        .map(|line| Error::at(DAY, input, line, NO_DIGIT))
+        .collect()
+}
+#[test]
+fn lines_without_any_digit_are_reported() {
+    let problems = validate("two1nine\nabc\neightwothree\nxyz\n");
+    assert_eq!(
+        problems
+            .iter()
+            .map(|problem| problem.line)
+            .collect::<Vec<_>>(),
+        vec![2, 4]
+    );
+}
+
+/// Part 2 for documents that spell their digits with another vocabulary.
+pub fn calibration_total(input: &str, vocabulary: &DigitVocabulary) -> Result<Answer, Error> {
+    calibration_sum(input, &vocabulary.scanner(), NO_DIGIT)
+}
+#[test]
+fn localized_documents_are_calibrated() {
+    let german = DigitVocabulary::german().case_insensitive(true);
+    assert_eq!(
+        calibration_total("Zweins\nxNULLx7\nachtsiebenull\nfünf\n", &german),
+        Ok(Answer::from(21u64 + 7 + 80 + 55))
+    );
+    let spanish = DigitVocabulary::spanish();
+    assert_eq!(
+        calibration_total("docho\nCuatro4\n", &spanish),
+        Ok(Answer::from(88u64 + 44))
+    );
+    let french = DigitVocabulary::french().case_insensitive(true);
+    assert_eq!(
+        calibration_total("ZÉROdeux\nseptrois\n", &french),
+        Ok(Answer::from(2u64 + 73))
+    );
+    let with_zero = DigitVocabulary::english().word("zero", 0);
+    assert_eq!(
+        calibration_total("zeroneight\n", &with_zero),
+        Ok(Answer::from(8u64))
+    );
+}
+
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    calibration_total(input, &DigitVocabulary::english())
+}
+#[test]
+fn part2_on_sample_input() {
+    SAMPLES.check("spelled_out", Part::Second, part2);
+}
+#[test]
+fn line_without_digits_is_an_error() {
+    let error = part2("two1nine\nabc\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
+}
+
+/// How `part2` used to work: searching each line once per digit name, then picking
+/// between the numeral and the name nearest each end. Kept to check and time the scanner
+/// against.
+#[cfg(test)]
+fn part2_by_searching(input: &str) -> Result<Answer, Error> {
+    input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let no_digit = || {
+                Error::at(
+                    DAY,
+                    input,
+                    line,
+                    "a digit or a digit's name somewhere on the line",
+                )
+            };
+            let first = match (
+                find_first_digit_value_with_index(line),
+                find_first_named_digit_value_with_index(line),
+            ) {
+                (None, None) => return Err(no_digit()),
+                (Some((_, digit_value)), None) => digit_value,
+                (None, Some((_, digit_value))) => digit_value,
+                (Some((d_index, d_value)), Some((n_index, n_value))) => {
+                    if d_index < n_index {
+                        d_value
+                    } else {
+                        n_value
                     }
-                };
+                }
+            };
 
-                let last = match (
-                    find_last_digit_value_with_index(line),
-                    find_last_named_digit_value_with_index(line),
-                ) {
-                    (None, None) => panic!(),
-                    (Some((_, digit_value)), None) => digit_value,
-                    (None, Some((_, digit_value))) => digit_value,
-                    (Some((d_index, d_value)), Some((n_index, n_value))) => {
-                        if d_index > n_index {
-                            d_value
-                        } else {
-                            n_value
-                        }
+            let last = match (
+                find_last_digit_value_with_index(line),
+                find_last_named_digit_value_with_index(line),
+            ) {
+                (None, None) => return Err(no_digit()),
+                (Some((_, digit_value)), None) => digit_value,
+                (None, Some((_, digit_value))) => digit_value,
+                (Some((d_index, d_value)), Some((n_index, n_value))) => {
+                    if d_index > n_index {
+                        d_value
+                    } else {
+                        n_value
                     }
-                };
-                first * 10 + last
-            })
-            .sum::<usize>()
-    )
+                }
+            };
+            Ok(first * 10 + last)
+        })
+        .sum::<Result<usize, Error>>()
+        .map(Answer::from)
 }
 #[test]
-fn part2_on_sample_input() {
-    let input = "two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen
-";
+fn scanner_agrees_with_searching() {
+    let input = "eightwo\noneight\n7pqrstsixteen\nzoneight234\nthreeight5fiveight\n";
+    assert_eq!(part2(input), part2_by_searching(input));
+}
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn scanner_agrees_with_searching_on_any_document(
+        lines in proptest::collection::vec(
+            "(one|two|three|four|five|six|seven|eight|nine|[a-z0-9]){1,10}",
+            1..20,
+        )
+    ) {
+        let input = lines.join("\n");
+        proptest::prop_assert_eq!(part2(&input), part2_by_searching(&input));
+    }
+}
+/// Times the scanner against searching per name; run it with
+/// `cargo test -p day1 --release -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn benchmark_scanner_against_searching() {
+    use std::time::Instant;
+
+    // a deterministic jumble of letters, numerals and overlapping names
+    let pieces = [
+        "eightwo",
+        "oneight",
+        "x",
+        "7",
+        "seven",
+        "abc",
+        "nine",
+        "twone",
+        "q",
+        "3",
+        "threeight",
+    ];
+    let mut seed = 1u64;
+    let mut input = String::new();
+    for _ in 0..100_000 {
+        for _ in 0..8 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            input.push_str(pieces[(seed >> 33) as usize % pieces.len()]);
+        }
+        input.push_str("1\n");
+    }
+    let time = |name: &str, solve: fn(&str) -> Result<Answer, Error>| {
+        let start = Instant::now();
+        let answer = solve(&input);
+        println!("{:>10}: {:?} in {:.2?}", name, answer, start.elapsed());
+        answer
+    };
     assert_eq!(
-        part2(input),
-        format!("{}", 29 + 83 + 13 + 24 + 42 + 14 + 76)
+        time("scanner", part2),
+        time("searching", part2_by_searching)
     );
 }
+
+/// `part1`, reading the document a line at a time.
+pub fn stream_part1(reader: impl BufRead) -> Result<Answer, StreamError> {
+    calibrate(reader, &Scanner::numerals(), NO_NUMERAL).map(Answer::from)
+}
+/// `part2`, reading the document a line at a time.
+pub fn stream_part2(reader: impl BufRead) -> Result<Answer, StreamError> {
+    calibrate(reader, &DigitVocabulary::english().scanner(), NO_DIGIT).map(Answer::from)
+}
+#[test]
+fn streaming_gives_the_same_answers() {
+    for (name, part) in [("example", Part::First), ("spelled_out", Part::Second)] {
+        let input = SAMPLES.input(name).unwrap();
+        let (streamed, whole) = match part {
+            Part::First => (stream_part1(input.as_bytes()), part1(input)),
+            Part::Second => (stream_part2(input.as_bytes()), part2(input)),
+        };
+        assert_eq!(streamed.unwrap(), whole.unwrap());
+    }
+}
+#[test]
+fn streaming_reports_a_running_total() {
+    let scanner = DigitVocabulary::english().scanner();
+    let totals = RunningTotals::new(
+        "two1nine\r\n\neightwo\nabc\n4\n".as_bytes(),
+        &scanner,
+        NO_DIGIT,
+    )
+    .map(|running| running.map_err(|error| error.to_string()))
+    .collect::<Vec<_>>();
+    assert_eq!(
+        totals,
+        vec![
+            Ok(RunningTotal { line: 1, total: 29 }),
+            Ok(RunningTotal {
+                line: 3,
+                total: 111
+            }),
+            Err(part2("two1nine\r\n\neightwo\nabc\n4\n")
+                .unwrap_err()
+                .to_string()),
+        ]
+    );
+}
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn streaming_agrees_with_reading_it_all(
+        lines in proptest::collection::vec("(one|two|eight|nine|[a-z0-9\r]){0,10}", 1..20)
+    ) {
+        let input = lines.join("\n");
+        let streamed = stream_part2(input.as_bytes()).map_err(|error| match error {
+            StreamError::Input(error) => error,
+            StreamError::Io(error) => panic!("reading from memory can't fail: {}", error),
+        });
+        proptest::prop_assert_eq!(streamed, part2(&input));
+    }
+}
+
+pub struct Day1;
+impl Solution for Day1 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn explain(&self, part: Part, input: &str) -> Option<String> {
+        let scanner = match part {
+            Part::First => Scanner::numerals(),
+            Part::Second => DigitVocabulary::english().scanner(),
+        };
+        Some(explain(input, &scanner).to_string())
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::VecDeque;
+
+use crate::DigitVocabulary;
+
+/// A digit found on a line, whether written as a numeral or spelled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// Where the token starts, as a byte offset into the line
+    pub offset: usize,
+    /// How many bytes of the line the token spans
+    pub len: usize,
+    pub value: u32,
+    pub spelled: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next: Vec<(char, usize)>,
+    /// Where to carry on matching from when no transition fits
+    fail: usize,
+    /// The words ending here, longest first, as their value and length in chars
+    output: Vec<(u32, usize)>,
+}
+
+/// Finds the digits on a line in a single pass, spelled out ones included.
+///
+/// The words are matched with an Aho-Corasick automaton, so overlapping words are all
+/// found: "eightwo" holds both an 8 and a 2, and "oneight" both a 1 and an 8.
+#[derive(Debug)]
+pub struct Scanner {
+    states: Vec<State>,
+    /// The most chars any word spans, so scanning only remembers that many offsets
+    longest: usize,
+    /// Whether words and lines are lowercased before they're compared
+    case_insensitive: bool,
+    unicode_numerals: bool,
+}
+impl Scanner {
+    /// Builds an automaton matching each of the vocabulary's words.
+    pub fn new(vocabulary: &DigitVocabulary) -> Scanner {
+        let case_insensitive = vocabulary.case_insensitive;
+        let mut states = vec![State::default()];
+        let mut longest = 1;
+        for (word, value) in vocabulary.words.iter() {
+            let (word, value) = (word.as_str(), *value);
+            let word = if case_insensitive {
+                word.to_lowercase()
+            } else {
+                word.to_string()
+            };
+            let mut state = 0;
+            for c in word.chars() {
+                state = match transition(&states, state, c) {
+                    Some(next) => next,
+                    None => {
+                        states.push(State::default());
+                        let next = states.len() - 1;
+                        states[state].next.push((c, next));
+                        next
+                    }
+                };
+            }
+            let chars = word.chars().count();
+            states[state].output.push((value, chars));
+            longest = longest.max(chars);
+        }
+        // link each state to the longest proper suffix of its word that's also a state,
+        // breadth first so that suffix has already been linked
+        let mut queue = states[0]
+            .next
+            .iter()
+            .map(|&(_, child)| child)
+            .collect::<VecDeque<_>>();
+        while let Some(parent) = queue.pop_front() {
+            for (c, child) in states[parent].next.clone() {
+                let mut fallback = states[parent].fail;
+                let fail = loop {
+                    if let Some(next) = transition(&states, fallback, c) {
+                        break next;
+                    }
+                    if fallback == 0 {
+                        break 0;
+                    }
+                    fallback = states[fallback].fail;
+                };
+                states[child].fail = fail;
+                let inherited = states[fail].output.clone();
+                states[child].output.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+        Scanner {
+            states,
+            longest,
+            case_insensitive,
+            unicode_numerals: vocabulary.unicode_numerals,
+        }
+    }
+    /// Only matches numerals.
+    pub fn numerals() -> Scanner {
+        Scanner::new(&DigitVocabulary::new())
+    }
+
+    fn step(&self, mut state: usize, c: char) -> usize {
+        loop {
+            if let Some(next) = transition(&self.states, state, c) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.states[state].fail;
+        }
+    }
+
+    /// Every digit on the line, in the order they end; that's also the order they start in,
+    /// unless one word can contain another.
+    pub fn scan<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Token> + 'a {
+        let mut state = 0;
+        // the start of each of the last few chars, to find where a word that just ended began
+        let mut starts = VecDeque::with_capacity(self.longest);
+        line.char_indices().flat_map(move |(offset, c)| {
+            let numeral = if self.unicode_numerals {
+                unicode_digit(c)
+            } else {
+                c.to_digit(10)
+            };
+            let end = offset + c.len_utf8();
+            let numeral = numeral.map(|value| Token {
+                offset,
+                len: c.len_utf8(),
+                value,
+                spelled: false,
+            });
+            let mut spelled = Vec::new();
+            // lowercasing can turn one char into several, which all start where it did
+            let mut step = |c| {
+                if starts.len() == self.longest {
+                    starts.pop_front();
+                }
+                starts.push_back(offset);
+                state = self.step(state, c);
+                spelled.extend(self.states[state].output.iter().map(|&(value, chars)| {
+                    let start = starts[starts.len() - chars];
+                    Token {
+                        offset: start,
+                        len: end - start,
+                        value,
+                        spelled: true,
+                    }
+                }));
+            };
+            if self.case_insensitive {
+                c.to_lowercase().for_each(&mut step);
+            } else {
+                step(c);
+            }
+            numeral.into_iter().chain(spelled)
+        })
+    }
+
+    /// The first and last digits on the line, which may be the same one.
+    pub fn first_and_last(&self, line: &str) -> Option<(Token, Token)> {
+        self.scan(line).fold(None, |found, token| match found {
+            None => Some((token, token)),
+            Some((first, last)) => Some((
+                if token.offset < first.offset {
+                    token
+                } else {
+                    first
+                },
+                if token.offset >= last.offset {
+                    token
+                } else {
+                    last
+                },
+            )),
+        })
+    }
+}
+/// The first code point of every script's decimal digits (general category Nd) as of
+/// Unicode 17.0, each of which is followed by the rest of its digits up to 9.
+const DECIMAL_ZEROS: [u32; 77] = [
+    0x30, 0x660, 0x6f0, 0x7c0, 0x966, 0x9e6, 0xa66, 0xae6, 0xb66, 0xbe6, 0xc66, 0xce6, 0xd66,
+    0xde6, 0xe50, 0xed0, 0xf20, 0x1040, 0x1090, 0x17e0, 0x1810, 0x1946, 0x19d0, 0x1a80, 0x1a90,
+    0x1b50, 0x1bb0, 0x1c40, 0x1c50, 0xa620, 0xa8d0, 0xa900, 0xa9d0, 0xa9f0, 0xaa50, 0xabf0, 0xff10,
+    0x104a0, 0x10d30, 0x10d40, 0x11066, 0x110f0, 0x11136, 0x111d0, 0x112f0, 0x11450, 0x114d0,
+    0x11650, 0x116c0, 0x116d0, 0x116da, 0x11730, 0x118e0, 0x11950, 0x11bf0, 0x11c50, 0x11d50,
+    0x11da0, 0x11de0, 0x11f50, 0x16130, 0x16a60, 0x16ac0, 0x16b50, 0x16d70, 0x1ccf0, 0x1d7ce,
+    0x1d7d8, 0x1d7e2, 0x1d7ec, 0x1d7f6, 0x1e140, 0x1e2f0, 0x1e4f0, 0x1e5f1, 0x1e950, 0x1fbf0,
+];
+/// The value of a decimal digit from any script, such as '٣' or '３'.
+fn unicode_digit(c: char) -> Option<u32> {
+    if c.is_ascii() {
+        return c.to_digit(10);
+    }
+    let c = c as u32;
+    let zero = match DECIMAL_ZEROS.binary_search(&c) {
+        Ok(_) => return Some(0),
+        Err(0) => return None,
+        Err(i) => DECIMAL_ZEROS[i - 1],
+    };
+    Some(c - zero).filter(|&value| value < 10)
+}
+#[test]
+fn digits_from_other_scripts_have_values() {
+    assert_eq!(unicode_digit('7'), Some(7));
+    assert_eq!(unicode_digit('٣'), Some(3));
+    assert_eq!(unicode_digit('३'), Some(3));
+    assert_eq!(unicode_digit('０'), Some(0));
+    assert_eq!(unicode_digit('９'), Some(9));
+    // the mathematical digits are five runs in a row
+    assert_eq!(unicode_digit('𝟎'), Some(0));
+    assert_eq!(unicode_digit('𝟡'), Some(9));
+    assert_eq!(unicode_digit('𝟿'), Some(9));
+    // digits from after Unicode 10
+    assert_eq!(unicode_digit('\u{10D33}'), Some(3));
+    assert_eq!(unicode_digit('\u{1E143}'), Some(3));
+    assert_eq!(unicode_digit('\u{1E2F3}'), Some(3));
+    assert_eq!(unicode_digit('\u{1FBF3}'), Some(3));
+    assert_eq!(unicode_digit('\u{1CCF5}'), Some(5));
+    // numeric, but not decimal digits
+    assert_eq!(unicode_digit('²'), None);
+    assert_eq!(unicode_digit('½'), None);
+    assert_eq!(unicode_digit('Ⅳ'), None);
+    assert_eq!(unicode_digit('x'), None);
+}
+#[test]
+fn decimal_zeros_each_start_ten_numerals() {
+    for zero in DECIMAL_ZEROS {
+        for c in zero..zero + 10 {
+            assert!(char::from_u32(c).unwrap().is_numeric(), "{:x}", c);
+        }
+    }
+}
+
+fn transition(states: &[State], state: usize, c: char) -> Option<usize> {
+    states[state]
+        .next
+        .iter()
+        .find(|&&(label, _)| label == c)
+        .map(|&(_, next)| next)
+}
+
+#[test]
+fn overlapping_words_are_all_found() {
+    let values = |line| {
+        crate::DigitVocabulary::english()
+            .scanner()
+            .scan(line)
+            .map(|token| (token.offset, token.value))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(values("eightwo"), vec![(0, 8), (4, 2)]);
+    assert_eq!(values("oneight"), vec![(0, 1), (2, 8)]);
+    assert_eq!(
+        values("twone3sevenine"),
+        vec![(0, 2), (2, 1), (5, 3), (6, 7), (10, 9)]
+    );
+    // a failed match has to resume from the suffix it shares with another word
+    assert_eq!(values("ninine"), vec![(2, 9)]);
+    assert_eq!(values("sevseven"), vec![(3, 7)]);
+}
+#[test]
+fn numerals_are_not_spelled() {
+    let tokens = Scanner::numerals().scan("a1two").collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        vec![Token {
+            offset: 1,
+            len: 1,
+            value: 1,
+            spelled: false
+        }]
+    );
+}
+
+/// Tries every vocabulary word at every char, which is obviously right if slow.
+#[cfg(test)]
+fn naive_scan(vocabulary: &DigitVocabulary, line: &str) -> Vec<Token> {
+    let lowercase = |s: &str| -> String {
+        if vocabulary.case_insensitive {
+            s.to_lowercase()
+        } else {
+            s.to_string()
+        }
+    };
+    let mut tokens = Vec::new();
+    for (offset, c) in line.char_indices() {
+        let numeral = match c {
+            '0'..='9' => Some(c as u32 - '0' as u32),
+            _ if vocabulary.unicode_numerals => unicode_digit(c),
+            _ => None,
+        };
+        tokens.extend(numeral.map(|value| Token {
+            offset,
+            len: c.len_utf8(),
+            value,
+            spelled: false,
+        }));
+        let rest = lowercase(&line[offset..]);
+        for (word, value) in vocabulary.words.iter() {
+            let word = lowercase(word);
+            if rest.starts_with(&word) {
+                // the word may not be as long in the line as it is lowercased
+                let len = line[offset..]
+                    .char_indices()
+                    .map(|(i, c)| i + c.len_utf8())
+                    .find(|&end| lowercase(&line[offset..offset + end]).len() >= word.len())
+                    .expect("the word is in the line");
+                tokens.push(Token {
+                    offset,
+                    len,
+                    value: *value,
+                    spelled: true,
+                });
+            }
+        }
+    }
+    tokens
+}
+#[cfg(test)]
+fn vocabularies() -> impl proptest::strategy::Strategy<Value = DigitVocabulary> {
+    use proptest::prelude::*;
+    (
+        prop_oneof![
+            Just(DigitVocabulary::english()),
+            Just(DigitVocabulary::french()),
+            Just(DigitVocabulary::german()),
+            Just(DigitVocabulary::spanish()),
+        ],
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(|(vocabulary, case_insensitive, unicode_numerals)| {
+            vocabulary
+                .case_insensitive(case_insensitive)
+                .unicode_numerals(unicode_numerals)
+        })
+}
+/// Lines mixing words from every vocabulary, in any case, with numerals from several
+/// scripts and letters of several widths.
+#[cfg(test)]
+fn lines() -> impl proptest::strategy::Strategy<Value = String> {
+    use proptest::prelude::*;
+    let words = [
+        DigitVocabulary::english(),
+        DigitVocabulary::french(),
+        DigitVocabulary::german(),
+        DigitVocabulary::spanish(),
+    ]
+    .into_iter()
+    .flat_map(|vocabulary| vocabulary.words)
+    .flat_map(|(word, _)| [word.to_uppercase(), word])
+    .collect::<Vec<_>>();
+    let piece = prop_oneof![
+        proptest::sample::select(words),
+        "[a-zA-Z0-9]{1,3}",
+        proptest::sample::select(vec!["é", "ß", "Ü", "😀", "٣", "３", "𝟡", "²"])
+            .prop_map(String::from),
+    ];
+    proptest::collection::vec(piece, 0..12).prop_map(|pieces| pieces.concat())
+}
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn scanning_agrees_with_trying_every_offset(vocabulary in vocabularies(), line in lines()) {
+        let scanned = vocabulary.scanner().scan(&line).collect::<Vec<_>>();
+        proptest::prop_assert_eq!(scanned, naive_scan(&vocabulary, &line));
+    }
+    #[test]
+    fn first_and_last_are_the_outermost_tokens(vocabulary in vocabularies(), line in lines()) {
+        let tokens = naive_scan(&vocabulary, &line);
+        let expected = tokens.first().zip(tokens.last()).map(|(&first, &last)| (first, last));
+        proptest::prop_assert_eq!(vocabulary.scanner().first_and_last(&line), expected);
+    }
+    #[test]
+    fn digits_of_a_script_count_up_from_zero(
+        zero in proptest::sample::select(vec![0x660u32, 0x6f0, 0x966, 0x9e6, 0xe50, 0xff10, 0x1d7ce, 0x1d7d8]),
+        value in 0..10u32,
+    ) {
+        proptest::prop_assert_eq!(unicode_digit(char::from_u32(zero + value).unwrap()), Some(value));
+    }
+    #[test]
+    fn only_numeric_chars_are_digits(c in proptest::prelude::any::<char>()) {
+        if unicode_digit(c).is_some() {
+            proptest::prop_assert!(c.is_numeric());
+        }
+    }
+}
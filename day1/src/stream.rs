@@ -0,0 +1,163 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use common::Error;
+
+use crate::{Scanner, DAY};
+
+/// Why a streamed document couldn't be calibrated.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Input(Error),
+}
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "couldn't read the document: {}", error),
+            StreamError::Input(error) => write!(f, "{}", error),
+        }
+    }
+}
+impl std::error::Error for StreamError {}
+
+/// The calibration total so far, after a line has been added to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunningTotal {
+    /// The line just added, 1-based
+    pub line: usize,
+    pub total: u64,
+}
+
+/// Calibrates a document a line at a time as it's read, so it never has to fit in memory:
+/// only the longest line does.
+///
+/// Yields the running total after each non-blank line, and stops after the first error;
+/// the last total is what `part1` or `part2` would give for the whole document.
+pub struct RunningTotals<'a, R> {
+    reader: R,
+    scanner: &'a Scanner,
+    expected: &'static str,
+    line: String,
+    line_number: usize,
+    total: u64,
+    failed: bool,
+}
+impl<'a, R: BufRead> RunningTotals<'a, R> {
+    /// Totals the lines with `scanner`, describing a line without digits as `expected`.
+    pub fn new(reader: R, scanner: &'a Scanner, expected: &'static str) -> Self {
+        RunningTotals {
+            reader,
+            scanner,
+            expected,
+            line: String::new(),
+            line_number: 0,
+            total: 0,
+            failed: false,
+        }
+    }
+    fn next_line(&mut self) -> io::Result<bool> {
+        self.line.clear();
+        let read = self.reader.read_line(&mut self.line)?;
+        // like `str::split('\n')`, leaving any '\r' in place
+        if self.line.ends_with('\n') {
+            self.line.pop();
+        }
+        self.line_number += 1;
+        Ok(read > 0)
+    }
+}
+impl<R: BufRead> Iterator for RunningTotals<'_, R> {
+    type Item = Result<RunningTotal, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            match self.next_line() {
+                Ok(false) => return None,
+                Ok(true) if self.line.is_empty() => continue,
+                Ok(true) => break,
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(StreamError::Io(error)));
+                }
+            }
+        }
+        let Some((first, last)) = self.scanner.first_and_last(&self.line) else {
+            self.failed = true;
+            let at_line_start = Error::at(DAY, &self.line, &self.line, self.expected);
+            return Some(Err(StreamError::Input(Error {
+                line: self.line_number,
+                ..at_line_start
+            })));
+        };
+        self.total += u64::from(first.value * 10 + last.value);
+        Some(Ok(RunningTotal {
+            line: self.line_number,
+            total: self.total,
+        }))
+    }
+}
+
+/// The calibration total of everything `reader` holds.
+pub fn calibrate(
+    reader: impl BufRead,
+    scanner: &Scanner,
+    expected: &'static str,
+) -> Result<u64, StreamError> {
+    RunningTotals::new(reader, scanner, expected)
+        .try_fold(0, |_, running| running.map(|running| running.total))
+}
+/// Streams a few gigabytes that are never all in memory at once; run it with
+/// `cargo test -p day1 --release -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn gigabytes_stream_in_constant_memory() {
+    use std::io::{BufReader, Read};
+
+    /// `chunk` over and over, `times` times, generated as it's read.
+    struct Repeated {
+        chunk: &'static [u8],
+        times: u64,
+        at: usize,
+    }
+    impl Read for Repeated {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.times == 0 {
+                return Ok(0);
+            }
+            let n = buf.len().min(self.chunk.len() - self.at);
+            buf[..n].copy_from_slice(&self.chunk[self.at..self.at + n]);
+            self.at += n;
+            if self.at == self.chunk.len() {
+                self.at = 0;
+                self.times -= 1;
+            }
+            Ok(n)
+        }
+    }
+
+    let chunk = "two1nine\neightwothree\nabcone2threexyz\n\nxtwone3four\r\n4nineeightseven2\n";
+    let times = (4u64 << 30) / chunk.len() as u64;
+    let per_chunk = crate::part2(chunk).unwrap().as_integer().unwrap() as u64;
+    let reader = BufReader::new(Repeated {
+        chunk: chunk.as_bytes(),
+        times,
+        at: 0,
+    });
+    let start = std::time::Instant::now();
+    let total = calibrate(
+        reader,
+        &crate::DigitVocabulary::english().scanner(),
+        crate::NO_DIGIT,
+    )
+    .unwrap();
+    println!(
+        "{} GiB in {:.2?}",
+        (times * chunk.len() as u64) >> 30,
+        start.elapsed()
+    );
+    assert_eq!(total, per_chunk * times);
+}
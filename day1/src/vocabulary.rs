@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+
+use common::{parse_at, Error};
+
+use crate::{Scanner, DAY};
+
+/// The words a calibration document spells its digits with, numerals aside.
+///
+/// Build one in code, start from one of the built-in languages, or read one from a file
+/// with a word per line and `case-insensitive` to ignore case:
+///
+/// ```text
+/// # Spanish, including zero
+/// case-insensitive
+/// cero = 0
+/// uno = 1
+/// ```
+///
+/// A `unicode-numerals` line, or [`DigitVocabulary::unicode_numerals`], also counts the
+/// decimal digits of other scripts, such as '٣' or '３', as numerals.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DigitVocabulary {
+    pub(crate) words: Vec<(String, u32)>,
+    pub(crate) case_insensitive: bool,
+    pub(crate) unicode_numerals: bool,
+}
+impl DigitVocabulary {
+    /// A vocabulary of numerals alone, to add words to.
+    pub fn new() -> DigitVocabulary {
+        DigitVocabulary::default()
+    }
+    /// Adds `word` as a spelling of `value`.
+    ///
+    /// Panics if `value` isn't a single digit, as a line's value has to be two digits.
+    pub fn word(mut self, word: impl Into<String>, value: u32) -> DigitVocabulary {
+        assert!(value <= 9, "{} isn't a single digit", value);
+        self.words.push((word.into(), value));
+        self
+    }
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> DigitVocabulary {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+    pub fn unicode_numerals(mut self, unicode_numerals: bool) -> DigitVocabulary {
+        self.unicode_numerals = unicode_numerals;
+        self
+    }
+    fn from_words(words: [&str; 10]) -> DigitVocabulary {
+        words
+            .into_iter()
+            .zip(0..)
+            .fold(DigitVocabulary::new(), |vocabulary, (word, value)| {
+                vocabulary.word(word, value)
+            })
+    }
+    /// The puzzle's vocabulary: "one" to "nine", with no "zero".
+    pub fn english() -> DigitVocabulary {
+        crate::DIGIT_NAMES
+            .iter()
+            .zip(1..)
+            .fold(DigitVocabulary::new(), |vocabulary, (word, value)| {
+                vocabulary.word(*word, value)
+            })
+    }
+    pub fn french() -> DigitVocabulary {
+        DigitVocabulary::from_words([
+            "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        ])
+    }
+    pub fn german() -> DigitVocabulary {
+        DigitVocabulary::from_words([
+            "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ])
+    }
+    pub fn spanish() -> DigitVocabulary {
+        DigitVocabulary::from_words([
+            "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+        ])
+    }
+
+    /// Reads a vocabulary in the format described above.
+    pub fn parse(text: &str) -> Result<DigitVocabulary, Error> {
+        let mut vocabulary = DigitVocabulary::new();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            match trimmed {
+                "case-insensitive" => {
+                    vocabulary.case_insensitive = true;
+                    continue;
+                }
+                "unicode-numerals" => {
+                    vocabulary.unicode_numerals = true;
+                    continue;
+                }
+                _ => {}
+            }
+            let (word, value) = trimmed
+                .split_once('=')
+                .ok_or_else(|| Error::at(DAY, text, line, "'<word> = <digit>'"))?;
+            let (word, value) = (word.trim(), value.trim());
+            if word.is_empty() {
+                return Err(Error::at(DAY, text, line, "a word before the '='"));
+            }
+            let value =
+                parse_at::<u32>(DAY, text, value, "a digit from 0 to 9").and_then(|n| match n {
+                    0..=9 => Ok(n),
+                    _ => Err(Error::at(DAY, text, value, "a digit from 0 to 9")),
+                })?;
+            vocabulary.words.push((word.to_string(), value));
+        }
+        Ok(vocabulary)
+    }
+    pub fn load(path: &Path) -> Result<DigitVocabulary, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read '{}': {}", path.display(), e))?;
+        DigitVocabulary::parse(&text)
+            .map_err(|e| format!("couldn't parse '{}':\n{}", path.display(), e.snippet()))
+    }
+
+    pub fn scanner(&self) -> Scanner {
+        Scanner::new(self)
+    }
+}
+
+#[test]
+fn vocabularies_read_from_text() {
+    let vocabulary = DigitVocabulary::parse(
+        "# German\ncase-insensitive\nunicode-numerals\n\nnull = 0\n fünf = 5 \n",
+    )
+    .unwrap();
+    assert_eq!(
+        vocabulary,
+        DigitVocabulary::new()
+            .word("null", 0)
+            .word("fünf", 5)
+            .case_insensitive(true)
+            .unicode_numerals(true)
+    );
+    let error = DigitVocabulary::parse("eins = 1\nzwölf = 12\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 9));
+    let error = DigitVocabulary::parse("eins 1\n").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 1));
+}
+#[test]
+#[should_panic(expected = "10 isn't a single digit")]
+fn words_are_single_digits() {
+    DigitVocabulary::english().word("ten", 10);
+}
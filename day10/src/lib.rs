@@ -1,3 +1,10 @@
+use common::{
+    grid_problems, samples, Answer, Error, Inspection, Inspector, Part, Samples, Solution,
+};
+
+const DAY: u8 = 10;
+const SAMPLES: Samples = samples!("square_loop", "complex_loop");
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Pipe {
     Vertical,
@@ -150,40 +157,67 @@ struct Grid {
     start: Position,
 }
 impl Grid {
-    fn parse(input: &str) -> Grid {
-        let mut start = Position::default();
-        let cells = input
+    fn parse(input: &str) -> Result<Grid, Error> {
+        let mut start = None;
+        let rows = input
             .trim()
             .split('\n')
             .enumerate()
             .map(|(y, line)| {
-                line.chars()
-                    .flat_map(<char as TryInto<GridCell>>::try_into)
+                line.char_indices()
                     .enumerate()
-                    .map(|(x, cell)| {
+                    .map(|(x, (byte_index, c))| {
+                        let cell = GridCell::try_from(c).map_err(|_| {
+                            Error::at(DAY, input, &line[byte_index..], "a pipe, 'S' or '.'")
+                        })?;
                         if let GridCell::Pipe(openings) = cell {
                             if openings == Pipe::Start.get_openings() {
-                                start = (x, y);
+                                start = Some((x, y));
                             }
                         }
-                        cell
+                        Ok(cell)
                     })
-                    .collect::<Vec<GridCell>>()
+                    .collect::<Result<Vec<GridCell>, Error>>()
+                    .map(|row| (line, row))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, Error>>()?;
+        let width = rows.first().map_or(0, |(_, row)| row.len());
+        if let Some((line, _)) = rows.iter().find(|(_, row)| row.len() != width) {
+            return Err(Error::at(
+                DAY,
+                input,
+                line,
+                format!("a row {} cells wide like the first", width),
+            ));
+        }
+        let cells = rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>();
+        let start = start.ok_or_else(|| {
+            Error::at(
+                DAY,
+                input,
+                &input[input.len()..],
+                "an 'S' marking the start",
+            )
+        })?;
         let (width, height) = (cells[0].len(), cells.len());
-        Grid {
+        Ok(Grid {
             width,
             height,
             cells,
             start,
-        }
+        })
     }
-    fn find_loop(&self, start: &Position) -> Vec<(Position, PipeOpenings)> {
+    /// Follows the pipes from `start` back round to it, or says where they lead nowhere.
+    fn find_loop(
+        &self,
+        input: &str,
+        start: &Position,
+    ) -> Result<Vec<(Position, PipeOpenings)>, Error> {
         let mut loop_segment: Vec<(Position, PipeOpenings)> =
             vec![(*start, Pipe::Start.get_openings())];
         let mut found_head = false;
         while !found_head {
+            let length = loop_segment.len();
             let &((tail_x, tail_y), tail_openings) = loop_segment.last().unwrap();
             for ((x, y), direction_from_tail) in
                 get_neighbors(&(tail_x, tail_y), self.width, self.height)
@@ -210,31 +244,155 @@ impl Grid {
                     }
                 }
             }
+            if !found_head && loop_segment.len() == length {
+                // every pipe leads on to exactly one other, so a walk that can't go on is stuck
+                let line = input.trim().split('\n').nth(tail_y).unwrap();
+                let (byte_index, _) = line.char_indices().nth(tail_x).unwrap();
+                return Err(Error::at(
+                    DAY,
+                    input,
+                    &line[byte_index..],
+                    "a pipe that leads on, to close the loop through 'S'",
+                ));
+            }
         }
-        loop_segment
+        Ok(loop_segment)
     }
 }
-pub fn part1(input: &str) -> String {
-    let grid = Grid::parse(input);
-    format!("{}", grid.find_loop(&grid.start).len() / 2)
+/// Besides being a grid of known tiles, there has to be exactly one start.
+pub fn validate(input: &str) -> Vec<Error> {
+    let mut problems = grid_problems(
+        DAY,
+        input,
+        |c| GridCell::try_from(c).is_ok(),
+        "a pipe, 'S' or '.'",
+    );
+    let starts = input.match_indices('S').collect::<Vec<_>>();
+    match starts.as_slice() {
+        [] => problems.push(Error::at(
+            DAY,
+            input,
+            &input[input.len()..],
+            "an 'S' marking the start",
+        )),
+        [_] => {}
+        [_, others @ ..] => problems.extend(
+            others
+                .iter()
+                .map(|&(i, _)| Error::at_offset(DAY, input, i, "only one 'S'")),
+        ),
+    }
+    if problems.is_empty() {
+        problems.extend(
+            Grid::parse(input)
+                .and_then(|grid| grid.find_loop(input, &grid.start))
+                .err(),
+        );
+    }
+    problems
+}
+#[test]
+fn starts_are_counted() {
+    assert_eq!(Error::positions(&validate("-L|F7\n7.-7|\n")), vec![(3, 1)]);
+    assert_eq!(
+        Error::positions(&validate("S-7S\n|.|.\nL-JS\n")),
+        vec![(1, 4), (3, 4)]
+    );
+    assert_eq!(validate(SAMPLES.input("complex_loop").unwrap()), vec![]);
+}
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    let grid = Grid::parse(input)?;
+    Ok((grid.find_loop(input, &grid.start)?.len() / 2).into())
 }
 #[test]
 fn part1_on_first_sample() {
-    let input = "-L|F7
-7S-7|
-L|7||
--L-J|
-L|-JF
-";
-    assert_eq!(part1(input), "4");
+    SAMPLES.check("square_loop", Part::First, part1);
 }
 #[test]
 fn part1_on_second_sample() {
-    let input = "7-F7-
-.FJ|7
-SJLL7
-|F--J
-LJ.LJ
-";
-    assert_eq!(part1(input), "8");
+    SAMPLES.check("complex_loop", Part::First, part1);
+}
+#[test]
+fn missing_start_is_an_error() {
+    let error = part1("-L|F7\n7.-7|\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 1));
+}
+#[test]
+fn broken_loop_is_an_error() {
+    let error = part1("-L|F7\n7S-7|\nL|7||\nL|-JF").unwrap_err();
+    assert_eq!((error.line, error.column), (4, 3));
+}
+#[test]
+fn ragged_grid_is_an_error() {
+    let error = part1("S-7\n|\nL-J").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
+}
+#[test]
+fn unknown_tile_is_an_error() {
+    let error = part1("-L|F7\n7S-X|\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 4));
+}
+
+fn loop_length(input: &str, _args: &[&str]) -> Inspection {
+    let grid = Grid::parse(input)?;
+    Ok(grid.find_loop(input, &grid.start)?.len().to_string())
+}
+fn show_start(input: &str, _args: &[&str]) -> Inspection {
+    let grid = Grid::parse(input)?;
+    let (x, y) = grid.start;
+    Ok(format!("column {}, line {}", x + 1, y + 1))
+}
+#[test]
+fn loop_length_is_twice_the_furthest_distance() {
+    assert_eq!(
+        loop_length(SAMPLES.input("complex_loop").unwrap(), &[]).unwrap(),
+        "16"
+    );
+}
+
+pub struct Day10;
+impl Solution for Day10 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Pipe Maze"
+    }
+    fn is_solved(&self, part: Part) -> bool {
+        matches!(part, Part::First)
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn inspectors(&self) -> &'static [Inspector] {
+        &[
+            Inspector {
+                command: "loop length",
+                args: "",
+                help: "how many pipes make up the loop through the start",
+                run: loop_length,
+            },
+            Inspector {
+                command: "start",
+                args: "",
+                help: "where the start is",
+                run: show_start,
+            },
+        ]
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, _input: &str) -> Option<Result<Answer, Error>> {
+        None
+    }
+}
+#[test]
+fn unsolved_part_has_no_answer() {
+    let input = SAMPLES.input("square_loop").unwrap();
+    assert_eq!(Day10.solve(Part::Second, input), None);
+    assert_eq!(Day10.part2(input), None);
 }
@@ -1,4 +1,8 @@
-use std::collections::{BTreeMap, BTreeSet};
+use common::{grid_problems, samples, Answer, Error, Samples, Solution};
+use std::collections::BTreeSet;
+
+const DAY: u8 = 11;
+const SAMPLES: Samples = samples!("example");
 
 type Position = (usize, usize);
 #[derive(Debug)]
@@ -20,72 +24,61 @@ impl TryFrom<char> for GridCell {
 struct Galaxies(BTreeSet<Position>);
 
 impl Galaxies {
-    fn parse(input: &str, expansion_factor: usize) -> Galaxies {
-        let lines = input.trim().split('\n');
-        let empty_rows: Vec<_> = lines
-            .clone()
+    fn parse(input: &str, expansion_factor: usize) -> Result<Galaxies, Error> {
+        let rows = input
+            .trim()
+            .split('\n')
+            .map(|line| {
+                line.char_indices()
+                    .map(|(byte_index, c)| {
+                        c.try_into()
+                            .map_err(|_| Error::at(DAY, input, &line[byte_index..], "'.' or '#'"))
+                    })
+                    .collect::<Result<Vec<GridCell>, Error>>()
+                    .map(|row| (line, row))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let width = rows.first().map_or(0, |(_, row)| row.len());
+        if let Some((line, _)) = rows.iter().find(|(_, row)| row.len() != width) {
+            return Err(Error::at(
+                DAY,
+                input,
+                line,
+                format!("a row {} cells wide like the first", width),
+            ));
+        }
+        let empty_rows: Vec<_> = rows
+            .iter()
             .enumerate()
-            .filter(|(_, line)| line.chars().all(|c| c.eq(&'.')))
+            .filter(|(_, (_, row))| row.iter().all(|cell| matches!(cell, GridCell::EmptySpace)))
             .map(|(y, _)| y)
             .collect();
-        let empty_columns: Vec<_> = lines
-            .clone()
-            .fold(BTreeMap::new(), |mut accum, line| {
-                if accum.is_empty() {
-                    for (x, c) in line.chars().enumerate() {
-                        accum.insert(
-                            x,
-                            match c {
-                                '.' => true,
-                                '#' => false,
-                                _ => panic!(),
-                            },
-                        );
-                    }
-                } else {
-                    for (x, c) in line.chars().enumerate() {
-                        if *accum.get(&x).unwrap() {
-                            accum.insert(
-                                x,
-                                match c {
-                                    '.' => true,
-                                    '#' => false,
-                                    _ => panic!(),
-                                },
-                            );
-                        }
-                    }
-                }
-
-                accum
+        let empty_columns: Vec<_> = (0..width)
+            .filter(|&x| {
+                rows.iter()
+                    .all(|(_, row)| matches!(row[x], GridCell::EmptySpace))
             })
-            .iter()
-            .filter(|(_, is_empty)| **is_empty)
-            .map(|(&x, _)| x)
             .collect();
         let mut galaxies = BTreeSet::new();
         let mut y_offset = 0;
-        for (y, line) in lines.enumerate() {
+        for (y, (_, row)) in rows.iter().enumerate() {
             if empty_rows.contains(&y) {
-                y_offset += (expansion_factor - 1);
+                y_offset += expansion_factor - 1;
             } else {
                 let mut x_offset = 0;
-                for (x, c) in line.chars().enumerate() {
+                for (x, cell) in row.iter().enumerate() {
                     if empty_columns.contains(&x) {
-                        x_offset += (expansion_factor - 1);
+                        x_offset += expansion_factor - 1;
                     } else {
-                        let cell = c.try_into().unwrap();
-                        {
-                            let cell_pos = (x + x_offset, y + y_offset);
-                            if let GridCell::Galaxy = cell {
-                                galaxies.insert(cell_pos);
-                            }
+                        let cell_pos = (x + x_offset, y + y_offset);
+                        if let GridCell::Galaxy = cell {
+                            galaxies.insert(cell_pos);
                         }
                     }
                 }
             }
         }
-        Galaxies(galaxies)
+        Ok(Galaxies(galaxies))
     }
     pub fn sum_shortest_pairwise_distances(&self) -> usize {
         use itertools::Itertools;
@@ -97,51 +90,70 @@ impl Galaxies {
             .sum()
     }
 }
-pub fn part1(input: &str) -> String {
-    let galaxies = Galaxies::parse(input, 2);
-    format!("{}", galaxies.sum_shortest_pairwise_distances())
+pub fn validate(input: &str) -> Vec<Error> {
+    grid_problems(DAY, input, |c| GridCell::try_from(c).is_ok(), "'.' or '#'")
+}
+#[test]
+fn stray_and_ragged_rows_are_reported() {
+    assert_eq!(
+        Error::positions(&validate("..#\n.x.\n#.\n")),
+        vec![(2, 2), (3, 1)]
+    );
+}
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    let galaxies = Galaxies::parse(input, 2)?;
+    Ok(galaxies.sum_shortest_pairwise_distances().into())
 }
 
 #[test]
 fn part1_on_sample() {
-    let input = "...#......
-.......#..
-#.........
-..........
-......#...
-.#........
-.........#
-..........
-.......#..
-#...#.....
-";
-    assert_eq!(part1(input), "374");
+    SAMPLES.check("example", common::Part::First, part1);
 }
 #[test]
 fn bigger_expansions_on_sample() {
-    let input = "...#......
-.......#..
-#.........
-..........
-......#...
-.#........
-.........#
-..........
-.......#..
-#...#.....
-";
+    let input = SAMPLES.input("example").unwrap();
     assert_eq!(
         1030,
-        Galaxies::parse(input, 10).sum_shortest_pairwise_distances()
+        Galaxies::parse(input, 10)
+            .unwrap()
+            .sum_shortest_pairwise_distances()
     );
     assert_eq!(
         8410,
-        Galaxies::parse(input, 100).sum_shortest_pairwise_distances()
+        Galaxies::parse(input, 100)
+            .unwrap()
+            .sum_shortest_pairwise_distances()
     );
 }
-pub fn part2(input: &str) -> String {
-    format!(
-        "{}",
-        Galaxies::parse(input, 1_000_000).sum_shortest_pairwise_distances()
-    )
+#[test]
+fn unknown_cell_is_an_error() {
+    let error = part1("...#..\n..*...\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 3));
+}
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    Ok(Galaxies::parse(input, 1_000_000)?
+        .sum_shortest_pairwise_distances()
+        .into())
+}
+
+pub struct Day11;
+impl Solution for Day11 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Cosmic Expansion"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
@@ -1,3 +1,8 @@
+use common::{parse_at, samples, Answer, Error, Samples, Solution};
+
+const DAY: u8 = 2;
+const SAMPLES: Samples = samples!("example");
+
 #[derive(Debug)]
 struct ColorCounts {
     r: u8,
@@ -9,85 +14,136 @@ struct Game {
     id: u8,
     queries: Vec<ColorCounts>,
 }
-fn parse_games(input: &str) -> Vec<Game> {
+fn parse_game(input: &str, line: &str) -> Result<Game, Error> {
+    let (game_id, queries) = line
+        .split_once(": ")
+        .ok_or_else(|| Error::at(DAY, input, line, "'Game <id>: ' before the draws"))?;
+    let (_, id) = game_id
+        .split_once(' ')
+        .ok_or_else(|| Error::at(DAY, input, game_id, "'Game <id>'"))?;
+    Ok(Game {
+        id: parse_at(DAY, input, id, "a game id between 0 and 255")?,
+        queries: queries
+            .split("; ")
+            .map(|query| {
+                query
+                    .split(", ")
+                    .try_fold(ColorCounts { r: 0, b: 0, g: 0 }, |mut accum, next| {
+                        let (count_text, color) = next
+                            .split_once(' ')
+                            .ok_or_else(|| Error::at(DAY, input, next, "'<count> <color>'"))?;
+                        let count: u8 =
+                            parse_at(DAY, input, count_text, "a cube count between 0 and 255")?;
+                        let total = match color {
+                            "red" => &mut accum.r,
+                            "blue" => &mut accum.b,
+                            "green" => &mut accum.g,
+                            _ => return Err(Error::at(DAY, input, color, "red, green or blue")),
+                        };
+                        *total = total.checked_add(count).ok_or_else(|| {
+                            Error::at(
+                                DAY,
+                                input,
+                                count_text,
+                                format!("at most 255 {} cubes in the draw altogether", color),
+                            )
+                        })?;
+                        Ok(accum)
+                    })
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+fn parse_games(input: &str) -> Result<Vec<Game>, Error> {
     input
         .trim_matches('\n')
         .split('\n')
-        .map(|line| line.split_once(": ").unwrap())
-        .map(|(game_id, queries)| Game {
-            id: game_id.split_once(' ').unwrap().1.parse::<u8>().unwrap(),
-            queries: queries
-                .split("; ")
-                .map(|query| {
-                    query
-                        .split(", ")
-                        .fold(ColorCounts { r: 0, b: 0, g: 0 }, |mut accum, next| {
-                            let (count, color) = next.split_once(' ').unwrap();
-                            let count = count.parse::<u8>().unwrap();
-                            match color {
-                                "red" => {
-                                    accum.r += count;
-                                }
-                                "blue" => {
-                                    accum.b += count;
-                                }
-                                "green" => {
-                                    accum.g += count;
-                                }
-                                _ => {}
-                            }
-                            accum
-                        })
-                })
-                .collect(),
-        })
+        .map(|line| parse_game(input, line))
         .collect()
 }
+/// Reports the first problem on each game that doesn't parse.
+pub fn validate(input: &str) -> Vec<Error> {
+    input
+        .trim_matches('\n')
+        .split('\n')
+        .filter_map(|line| parse_game(input, line).err())
+        .collect()
+}
+#[test]
+fn every_bad_game_is_reported() {
+    let problems =
+        validate("Game 1: 3 blue, 4 red\nGame 2: 1 purple\nGame 3: 2 green\nGame 4 2 green\n");
+    assert_eq!(Error::positions(&problems), vec![(2, 11), (4, 1)]);
+}
+#[test]
+fn overflowing_draw_is_reported() {
+    let problems = validate("Game 1: 200 red, 100 red\n");
+    assert_eq!(Error::positions(&problems), vec![(1, 18)]);
+}
 
-pub fn part1(input: &str) -> String {
+pub fn part1(input: &str) -> Result<Answer, Error> {
     const MAX_RED: u8 = 12;
     const MAX_GREEN: u8 = 13;
     const MAX_BLUE: u8 = 14;
-    let games: Vec<Game> = parse_games(input);
+    let games: Vec<Game> = parse_games(input)?;
     let allowed_games = games.iter().filter(|game| {
         game.queries
             .iter()
             .all(|q| q.r <= MAX_RED && q.g <= MAX_GREEN && q.b <= MAX_BLUE)
     });
-    format!(
-        "{}",
-        allowed_games.map(|game| game.id as usize).sum::<usize>()
-    )
+    Ok(allowed_games
+        .map(|game| game.id as usize)
+        .sum::<usize>()
+        .into())
 }
 
 #[test]
 fn test_part1_on_sample() {
-    let sample = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-";
-    assert_eq!(part1(sample), format!("{}", 1 + 2 + 5));
+    SAMPLES.check("example", common::Part::First, part1);
+}
+#[test]
+fn unknown_color_is_an_error() {
+    let error = part1("Game 1: 3 blue\nGame 2: 8 purple\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 11));
 }
 
-pub fn part2(input: &str) -> String {
-    let games: Vec<Game> = parse_games(input);
-    format!(
-        "{}",
-        games
-            .iter()
-            .map(|game| {
-                game.queries
-                    .iter()
-                    .fold(ColorCounts { r: 0, b: 0, g: 0 }, |mut accum, next| {
-                        accum.r = accum.r.max(next.r);
-                        accum.g = accum.g.max(next.g);
-                        accum.b = accum.b.max(next.b);
-                        accum
-                    })
-            })
-            .map(|min_counts| min_counts.r as usize * min_counts.g as usize * min_counts.b as usize)
-            .sum::<usize>()
-    )
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    let games: Vec<Game> = parse_games(input)?;
+    Ok(games
+        .iter()
+        .map(|game| {
+            game.queries
+                .iter()
+                .fold(ColorCounts { r: 0, b: 0, g: 0 }, |mut accum, next| {
+                    accum.r = accum.r.max(next.r);
+                    accum.g = accum.g.max(next.g);
+                    accum.b = accum.b.max(next.b);
+                    accum
+                })
+        })
+        .map(|min_counts| min_counts.r as usize * min_counts.g as usize * min_counts.b as usize)
+        .sum::<usize>()
+        .into())
+}
+
+pub struct Day2;
+impl Solution for Day2 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Cube Conundrum"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
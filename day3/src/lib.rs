@@ -1,3 +1,8 @@
+use common::{grid_problems, samples, Answer, Error, Samples, Solution};
+
+const DAY: u8 = 3;
+const SAMPLES: Samples = samples!("example");
+
 #[derive(Clone, Copy)]
 struct Position {
     x: u8,
@@ -44,21 +49,22 @@ struct Parsing {
     found_symbols: Vec<Symbol>,
 }
 
-fn parse_schematic(input: &str) -> Parsing {
+fn parse_schematic(input: &str) -> Result<Parsing, Error> {
     input
         .split('\n')
         .filter(|line| !line.is_empty())
         .enumerate()
-        .fold(
+        .try_fold(
             Parsing {
                 current_number: None,
                 found_numbers: vec![],
                 found_symbols: vec![],
             },
             |accum, (y, line)| {
-                let mut accum_with_line =
-                    line.chars().enumerate().fold(accum, |mut accum, (x, c)| {
-                        if c == '.' {
+                let mut accum_with_line = line.char_indices().enumerate().try_fold(
+                    accum,
+                    |mut accum, (x, (byte_index, c))| {
+                        Ok(if c == '.' {
                             if let Some(number) = accum.current_number {
                                 accum.found_numbers.push(number);
                             }
@@ -66,18 +72,29 @@ fn parse_schematic(input: &str) -> Parsing {
                                 current_number: None,
                                 ..accum
                             }
-                        } else if c.is_numeric() {
+                        } else if let Some(digit) = c.to_digit(10) {
                             let pos = Position::from_usize(x, y);
+                            let val = accum
+                                .current_number
+                                .map_or(Some(0), |n| n.val.checked_mul(10))
+                                .and_then(|val| val.checked_add(digit))
+                                .ok_or_else(|| {
+                                    Error::at(
+                                        DAY,
+                                        input,
+                                        &line[byte_index..],
+                                        "the end of a number below 2^32",
+                                    )
+                                })?;
                             Parsing {
                                 current_number: Some(Number {
-                                    val: c.to_digit(10).unwrap()
-                                        + accum.current_number.map_or(0, |n| n.val * 10),
+                                    val,
                                     start: accum.current_number.map_or(pos, |n| n.start),
                                     end: pos,
                                 }),
                                 ..accum
                             }
-                        } else if !c.is_alphanumeric() {
+                        } else if c.is_ascii_punctuation() {
                             accum.found_symbols.push(Symbol {
                                 val: c,
                                 pos: Position::from_usize(x, y),
@@ -90,93 +107,146 @@ fn parse_schematic(input: &str) -> Parsing {
                                 ..accum
                             }
                         } else {
-                            panic!()
-                        }
-                    });
+                            return Err(Error::at(
+                                DAY,
+                                input,
+                                &line[byte_index..],
+                                "a digit, a '.' or a symbol",
+                            ));
+                        })
+                    },
+                )?;
                 // terminate current part number at line end
                 if let Some(number) = accum_with_line.current_number {
                     accum_with_line.found_numbers.push(number);
                     accum_with_line.current_number = None;
                 }
-                accum_with_line
+                Ok(accum_with_line)
             },
         )
 }
 
-pub fn part1(input: &str) -> String {
-    let line_length = input.find('\n').unwrap();
-    let line_count = input.len() / line_length;
-    let parsed = parse_schematic(input);
-    format!(
-        "{}",
-        parsed
-            .found_numbers
-            .iter()
-            .filter(|number| {
-                parsed
-                    .found_symbols
-                    .iter()
-                    .any(|symbol| number.is_valid_part_number(line_length, line_count, &symbol.pos))
-            })
-            .map(|n| n.val as usize)
-            .sum::<usize>()
-    )
+/// The width of the first row, and how many rows there are, skipping blank lines like
+/// `parse_schematic` does.
+fn measure_schematic(input: &str) -> Result<(usize, usize), Error> {
+    let mut rows = input.split('\n').filter(|line| !line.is_empty());
+    let first = rows
+        .next()
+        .ok_or_else(|| Error::at(DAY, input, &input[input.len()..], "a row of the schematic"))?;
+    Ok((first.chars().count(), 1 + rows.count()))
+}
+#[test]
+fn schematics_are_measured_by_their_rows() {
+    assert_eq!(measure_schematic("\n467..\n...*.\n"), Ok((5, 2)));
+    assert_eq!(measure_schematic("467.."), Ok((5, 1)));
+    assert_eq!(
+        measure_schematic("\n\n").unwrap_err().expected,
+        "a row of the schematic"
+    );
+}
+
+/// Rows must all be as wide as each other, since `part1` and `part2` work out their
+/// count from the width.
+pub fn validate(input: &str) -> Vec<Error> {
+    let mut problems = grid_problems(
+        DAY,
+        input,
+        |c| c.is_ascii_digit() || c.is_ascii_punctuation(),
+        "a digit, a '.' or a symbol",
+    );
+    if problems.is_empty() {
+        problems.extend(parse_schematic(input).err());
+    }
+    problems
+}
+#[test]
+fn ragged_schematic_is_reported() {
+    let problems = validate("467..114..\n...*......\n..35..633\n");
+    assert_eq!(Error::positions(&problems), vec![(3, 1)]);
+}
+#[test]
+fn overlong_number_is_reported() {
+    let problems = validate("99999999999*\n");
+    assert_eq!(Error::positions(&problems), vec![(1, 10)]);
+}
+
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    let (line_length, line_count) = measure_schematic(input)?;
+    let parsed = parse_schematic(input)?;
+    Ok(parsed
+        .found_numbers
+        .iter()
+        .filter(|number| {
+            parsed
+                .found_symbols
+                .iter()
+                .any(|symbol| number.is_valid_part_number(line_length, line_count, &symbol.pos))
+        })
+        .map(|n| n.val as usize)
+        .sum::<usize>()
+        .into())
 }
 #[test]
 fn part1_on_sample_input() {
-    let input = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
-";
-    assert_eq!(part1(input), "4361")
+    SAMPLES.check("example", common::Part::First, part1);
+}
+#[test]
+fn letters_are_an_error() {
+    let error = part1("467..114..\n...*..x...\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 7));
+}
+#[test]
+fn only_ascii_punctuation_is_a_symbol() {
+    for input in ["467..\n..é..\n", "467..\r\n...*.\r\n"] {
+        let error = part1(input).unwrap_err();
+        assert_eq!(validate(input).first(), Some(&error));
+    }
 }
 
-pub fn part2(input: &str) -> String {
-    let line_length = input.find('\n').unwrap();
-    let line_count = input.len() / line_length;
-    let parsed = parse_schematic(input);
-    format!(
-        "{}",
-        parsed
-            .found_symbols
-            .iter()
-            .filter(|s| s.val == '*')
-            .map(|symbol| {
-                parsed
-                    .found_numbers
-                    .iter()
-                    .filter(|number| {
-                        number.is_valid_part_number(line_length, line_count, &symbol.pos)
-                    })
-                    .map(|number| (number.val as usize))
-                    .collect::<Vec<_>>()
-            })
-            .filter(|valid_numbers| valid_numbers.len() == 2)
-            .map(|part_numbers| part_numbers.iter().product::<usize>())
-            .sum::<usize>()
-    )
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    let (line_length, line_count) = measure_schematic(input)?;
+    let parsed = parse_schematic(input)?;
+    Ok(parsed
+        .found_symbols
+        .iter()
+        .filter(|s| s.val == '*')
+        .map(|symbol| {
+            parsed
+                .found_numbers
+                .iter()
+                .filter(|number| number.is_valid_part_number(line_length, line_count, &symbol.pos))
+                .map(|number| number.val as usize)
+                .collect::<Vec<_>>()
+        })
+        .filter(|valid_numbers| valid_numbers.len() == 2)
+        .map(|part_numbers| part_numbers.iter().product::<usize>())
+        .sum::<usize>()
+        .into())
 }
 
 #[test]
 fn part2_on_sample_input() {
-    let input = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
+    SAMPLES.check("example", common::Part::Second, part2);
+}
 
-";
-    assert_eq!(part2(input), "467835");
+pub struct Day3;
+impl Solution for Day3 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Gear Ratios"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
@@ -1,86 +1,200 @@
+use common::{parse_at, samples, Answer, Error, Samples, Solution};
 use std::collections::BinaryHeap;
 
-fn parse_numbers(winning: &str) -> impl Iterator<Item = u8> + '_ {
-    winning
+const DAY: u8 = 4;
+const SAMPLES: Samples = samples!("example");
+
+fn parse_numbers<'a>(
+    input: &'a str,
+    numbers: &'a str,
+) -> impl Iterator<Item = Result<u8, Error>> + 'a {
+    numbers
         .trim()
         .split(' ')
         .filter(|chars| !chars.is_empty())
-        .map(|n| n.parse::<u8>().unwrap())
+        .map(|n| parse_at(DAY, input, n, "a number between 0 and 255"))
+}
+/// Splits a card into its winning and drawn numbers.
+fn parse_line<'a>(input: &str, line: &'a str) -> Result<(&'a str, &'a str), Error> {
+    let (_, rest) = line
+        .split_once(':')
+        .ok_or_else(|| Error::at(DAY, input, line, "'Card <id>:' before the numbers"))?;
+    rest.split_once('|')
+        .ok_or_else(|| Error::at(DAY, input, rest, "a '|' between the two sets of numbers"))
 }
-fn parse_line(line: &str) -> (impl Iterator<Item = u8> + '_, impl Iterator<Item = u8> + '_) {
-    let (_, rest) = line.split_once(':').unwrap();
-    let (winning, drawn) = rest.split_once('|').unwrap();
-    let winning = parse_numbers(winning);
-    let drawn = parse_numbers(drawn);
-    (winning, drawn)
+/// How many of the card's drawn numbers are among its winning ones.
+fn count_matches(input: &str, line: &str) -> Result<usize, Error> {
+    let (winning, drawn) = parse_line(input, line)?;
+    let winning = parse_numbers(input, winning).collect::<Result<BinaryHeap<u8>, Error>>()?;
+    parse_numbers(input, drawn).try_fold(0, |matches, number| {
+        let number = number?;
+        Ok(matches + usize::from(winning.iter().any(|w| *w == number)))
+    })
 }
 
-pub fn part1(input: &str) -> String {
-    format!(
-        "{}",
-        input
-            .split('\n')
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                let (winning, drawn) = parse_line(line);
-                let winning = winning.collect::<BinaryHeap<u8>>();
-                let scoring = drawn
-                    .filter(|number| winning.iter().any(|w| w == number))
-                    .count();
-                if scoring == 0 {
-                    0
-                } else {
-                    2usize.pow(scoring as u32 - 1)
-                }
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let scoring = count_matches(input, line)?;
+            Ok(if scoring == 0 {
+                0
+            } else {
+                2usize.pow(scoring as u32 - 1)
             })
-            .sum::<usize>()
-    )
+        })
+        .sum::<Result<usize, Error>>()
+        .map(Answer::from)
 }
 #[test]
 fn part1_on_sample() {
-    let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-";
-    assert_eq!(part1(input), "13")
+    SAMPLES.check("example", common::Part::First, part1);
 }
-pub fn part2(input: &str) -> String {
-    let line_count = input.trim().split('\n').count();
-    format!(
-        "{}",
-        input
-            .split('\n')
-            .filter(|line| !line.is_empty())
-            .enumerate()
-            .fold(
-                std::iter::repeat(1).take(line_count).collect::<Vec<_>>(),
-                |mut accum, (card_index, line)| {
-                    let card_count = accum[card_index];
-                    let (winning, drawn) = parse_line(line);
-                    let winning = winning.collect::<BinaryHeap<u8>>();
-                    let matches = drawn.filter(|d| winning.iter().any(|w| w == d)).count();
-                    for offset in 1..=matches {
-                        accum[card_index + offset] += card_count;
-                    }
-                    accum
-                },
-            )
-            .iter()
-            .sum::<usize>()
+#[test]
+fn missing_separator_is_an_error() {
+    let error = part1("Card 1: 41 48 83 86 17 | 83 86  6\nCard 2: 13 32 20 61 30\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 8));
+}
+/// A card winning copies of more cards than follow it, which `part2` can't hand out.
+fn wins_past_the_end(input: &str, line: &str, cards_left: usize) -> Error {
+    Error::at(
+        DAY,
+        input,
+        line,
+        format!(
+            "at most {} matches, one per card after this one",
+            cards_left
+        ),
     )
 }
+/// Besides parsing, every card should have as many numbers on each side as the first, and
+/// no card should win copies of cards past the last one.
+pub fn validate(input: &str) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let mut first_counts = None;
+    let cards = input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    for (card_index, &line) in cards.iter().enumerate() {
+        let (winning, drawn) = match parse_line(input, line) {
+            Ok(sides) => sides,
+            Err(problem) => {
+                problems.push(problem);
+                continue;
+            }
+        };
+        let mut counts = [0; 2];
+        for (count, side) in counts.iter_mut().zip([winning, drawn]) {
+            for number in parse_numbers(input, side) {
+                match number {
+                    Ok(_) => *count += 1,
+                    Err(problem) => problems.push(problem),
+                }
+            }
+        }
+        let [expected_winning, expected_drawn] = *first_counts.get_or_insert(counts);
+        if counts[0] != expected_winning {
+            problems.push(Error::at(
+                DAY,
+                input,
+                winning,
+                format!(
+                    "{} winning numbers like the first card, not {}",
+                    expected_winning, counts[0]
+                ),
+            ));
+        }
+        if counts[1] != expected_drawn {
+            problems.push(Error::at(
+                DAY,
+                input,
+                drawn,
+                format!(
+                    "{} drawn numbers like the first card, not {}",
+                    expected_drawn, counts[1]
+                ),
+            ));
+        }
+        let cards_left = cards.len() - card_index - 1;
+        if let Ok(matches) = count_matches(input, line) {
+            if matches > cards_left {
+                problems.push(wins_past_the_end(input, line, cards_left));
+            }
+        }
+    }
+    problems
+}
+#[test]
+fn cards_of_the_wrong_size_are_reported() {
+    let problems = validate(
+        "Card 1: 41 48 | 83 86  6\nCard 2: 13 | 61 30 68\nCard 3: 1 x | 2 3 4\nCard 4 1 2\n",
+    );
+    assert_eq!(
+        Error::positions(&problems),
+        vec![(2, 8), (3, 11), (3, 8), (4, 1)]
+    );
+}
+#[test]
+fn winning_past_the_last_card_is_reported() {
+    let input = "Card 1: 41 48 | 41 48\nCard 2: 41 48 | 41 86\n";
+    let problems = validate(input);
+    assert_eq!(Error::positions(&problems), vec![(1, 1), (2, 1)]);
+    let error = part2(input).unwrap_err();
+    assert_eq!((error.line, error.column), (1, 1));
+}
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    let cards = input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    Ok(cards
+        .iter()
+        .enumerate()
+        .try_fold(
+            std::iter::repeat_n(1, cards.len()).collect::<Vec<_>>(),
+            |mut accum, (card_index, line)| {
+                let card_count = accum[card_index];
+                let matches = count_matches(input, line)?;
+                let cards_left = cards.len() - card_index - 1;
+                if matches > cards_left {
+                    return Err(wins_past_the_end(input, line, cards_left));
+                }
+                for offset in 1..=matches {
+                    accum[card_index + offset] += card_count;
+                }
+                Ok::<_, Error>(accum)
+            },
+        )?
+        .iter()
+        .sum::<usize>()
+        .into())
+}
 
 #[test]
 fn part2_on_sample() {
-    let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-";
-    assert_eq!(part2(input), "30");
+    SAMPLES.check("example", common::Part::Second, part2);
+}
+
+pub struct Day4;
+impl Solution for Day4 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Scratchcards"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
@@ -1,6 +1,10 @@
+use common::{parse_at, samples, Answer, Error, Inspection, Inspector, Samples, Solution};
 use std::collections::HashMap;
 use std::ops::RangeInclusive;
 
+const DAY: u8 = 5;
+const SAMPLES: Samples = samples!("example", "single_seed");
+
 #[derive(Debug)]
 struct Mapping {
     source_start: usize,
@@ -17,9 +21,6 @@ impl Mapping {
     fn destination_end_inside(&self) -> usize {
         self.destination_start + self.count - 1
     }
-    fn destination_end_outside(&self) -> usize {
-        self.destination_end_inside() + 1
-    }
 }
 #[derive(PartialEq, Eq, Debug)]
 enum Value {
@@ -132,121 +133,198 @@ fn test_map_range() {
     );
 }
 
-pub fn part1(input: &str) -> String {
-    #[derive(Debug, Default)]
-    struct Almanac<'a> {
-        seeds: Vec<usize>,
-        mappings: HashMap<(&'a str, &'a str), Vec<Mapping>>,
-    }
-    impl Almanac<'_> {
-        pub fn map_value(&self, category: &str, value: usize) -> (&str, usize) {
-            let ((_, new_dest), entries) = self
-                .mappings
+/// Splits an almanac section into its header and what follows the ':'.
+fn split_section<'a>(input: &str, section: &'a str) -> Result<(&'a str, &'a str), Error> {
+    section
+        .split_once(':')
+        .ok_or_else(|| Error::at(DAY, input, section, "a ':' after the section header"))
+}
+fn parse_mapping(input: &str, line: &str) -> Result<Mapping, Error> {
+    let mut split = line.split_whitespace();
+    let mut next_number = |expected: &str| {
+        split
+            .next()
+            .ok_or_else(|| {
+                Error::at(
+                    DAY,
+                    input,
+                    line,
+                    format!("3 numbers, missing the {}", expected),
+                )
+            })
+            .and_then(|n| parse_at(DAY, input, n, expected))
+    };
+    Ok(Mapping {
+        destination_start: next_number("destination start")?,
+        source_start: next_number("source start")?,
+        count: next_number("count")?,
+    })
+}
+fn parse_header<'a>(input: &str, header: &'a str) -> Result<(&'a str, &'a str), Error> {
+    let malformed_header = || {
+        Error::at(
+            DAY,
+            input,
+            header,
+            "a '<source>-to-<destination> map' header",
+        )
+    };
+    let (dest_cat, header_rest) = header.split_once('-').ok_or_else(malformed_header)?;
+    let (source_cat, _) = header_rest
+        .strip_prefix("to-")
+        .and_then(|source_cat| source_cat.split_once(' '))
+        .ok_or_else(malformed_header)?;
+    Ok((dest_cat, source_cat))
+}
+fn no_seeds(input: &str) -> Error {
+    Error::at(DAY, input, input, "at least one seed")
+}
+
+#[derive(Debug, Default)]
+struct Almanac<'a> {
+    seeds: Vec<usize>,
+    mappings: HashMap<(&'a str, &'a str), Vec<Mapping>>,
+}
+impl Almanac<'_> {
+    pub fn map_value(&self, category: &str, value: usize) -> Option<(&str, usize)> {
+        let ((_, new_dest), entries) = self.mappings.iter().find(|((s, _), _)| *s == category)?;
+        Some((
+            new_dest,
+            entries
                 .iter()
-                .find(|((s, _), _)| *s == category)
-                .unwrap();
-            (
-                new_dest,
-                entries
-                    .iter()
-                    .find(|m| m.source_start <= value && value <= m.source_start + m.count)
-                    .map_or(value, |m| m.destination_start + (value - m.source_start)),
-            )
+                .find(|m| m.source_start <= value && value <= m.source_start + m.count)
+                .map_or(value, |m| m.destination_start + (value - m.source_start)),
+        ))
+    }
+    /// Follows a seed through every map, listing each category it passes through.
+    pub fn locate(&self, input: &str, seed: usize) -> Result<Vec<(&str, usize)>, Error> {
+        let mut path = vec![("seed", seed)];
+        while let Some(&(source, value)) = path.last().filter(|(c, _)| *c != "location") {
+            let (next, value) = self.map_value(source, value).ok_or_else(|| {
+                Error::at(
+                    DAY,
+                    input,
+                    &input[input.len()..],
+                    format!("a map from '{}' to another category", source),
+                )
+            })?;
+            // the category names are slices of their map's header, so errors can point there
+            if path.iter().any(|&(visited, _)| visited == next) {
+                return Err(Error::at(
+                    DAY,
+                    input,
+                    next,
+                    format!("a category other than '{}', which comes round again", next),
+                ));
+            }
+            path.push((next, value));
         }
+        Ok(path)
     }
-    fn parse_almanac(input: &str) -> Almanac {
-        input.split("\n\n").fold(
-            Almanac {
-                seeds: Vec::new(),
-                mappings: HashMap::new(),
-            },
-            |mut almanac, next| {
-                let (header, rest) = next.split_once(':').unwrap();
-                if almanac.seeds.is_empty() {
+}
+fn parse_almanac(input: &str) -> Result<Almanac<'_>, Error> {
+    input.split("\n\n").try_fold(
+        Almanac {
+            seeds: Vec::new(),
+            mappings: HashMap::new(),
+        },
+        |mut almanac, next| {
+            let (header, rest) = split_section(input, next)?;
+            if almanac.seeds.is_empty() {
+                for seed in rest.trim().split(' ') {
                     almanac
                         .seeds
-                        .extend(rest.trim().split(' ').map(|s| s.parse::<usize>().unwrap()));
-                    almanac
-                } else {
-                    let (dest_cat, header_rest) = header.split_once('-').unwrap();
-                    // "-to-" is indices 0->2
-                    let (_, source_cat) = header_rest.split_at(3);
-                    let (source_cat, _) = source_cat.split_once(' ').unwrap();
-                    almanac.mappings.entry((dest_cat, source_cat)).or_default();
-                    let for_cat_pair = almanac.mappings.get_mut(&(dest_cat, source_cat)).unwrap();
-                    for line in rest.trim().split('\n') {
-                        let mut split = line.split_whitespace();
-                        let destination_start: usize = split.next().unwrap().parse().unwrap();
-                        let source_start: usize = split.next().unwrap().parse().unwrap();
-                        let count: usize = split.next().unwrap().parse().unwrap();
-                        for_cat_pair.push(Mapping {
-                            destination_start,
-                            source_start,
-                            count,
-                        });
-                    }
-                    almanac
+                        .push(parse_at(DAY, input, seed, "a seed number")?);
                 }
-            },
-        )
-    }
-    let almanac = parse_almanac(input);
-    format!(
-        "{}",
-        almanac
-            .seeds
-            .iter()
-            .map(|seed_number| {
-                let mut mapped_value = *seed_number;
-                let mut source = "seed";
-                while source != "location" {
-                    (source, mapped_value) = almanac.map_value(source, mapped_value);
+                Ok(almanac)
+            } else {
+                let (dest_cat, source_cat) = parse_header(input, header)?;
+                let for_cat_pair = almanac.mappings.entry((dest_cat, source_cat)).or_default();
+                for line in rest.trim().split('\n') {
+                    for_cat_pair.push(parse_mapping(input, line)?);
                 }
-                mapped_value
-            })
-            .min()
-            .unwrap()
+                Ok(almanac)
+            }
+        },
     )
 }
-#[test]
-fn part1_on_sample() {
-    let input = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
 
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
+/// Checks the seeds, every map header and every mapping line, rather than stopping at
+/// the first problem like `parse_almanac` does.
+pub fn validate(input: &str) -> Vec<Error> {
+    let mut problems = Vec::new();
+    for (i, section) in input.split("\n\n").enumerate() {
+        let (header, rest) = match split_section(input, section) {
+            Ok(split) => split,
+            Err(problem) => {
+                problems.push(problem);
+                continue;
+            }
+        };
+        if i == 0 {
+            let seeds = rest.split_whitespace().collect::<Vec<_>>();
+            if seeds.is_empty() {
+                problems.push(no_seeds(input));
+            }
+            problems.extend(
+                seeds
+                    .into_iter()
+                    .filter_map(|seed| parse_at::<usize>(DAY, input, seed, "a seed number").err()),
+            );
+        } else {
+            problems.extend(parse_header(input, header).err());
+            problems.extend(
+                rest.trim()
+                    .split('\n')
+                    .filter_map(|line| parse_mapping(input, line).err()),
+            );
+        }
+    }
+    // the maps have to lead from seeds to locations, whichever seed is followed
+    if problems.is_empty() {
+        problems.extend(
+            parse_almanac(input)
+                .and_then(|almanac| almanac.locate(input, 0).map(drop))
+                .err(),
+        );
+    }
+    problems
+}
+#[test]
+fn every_bad_seed_header_and_mapping_is_reported() {
+    let problems = validate(
+        "seeds: 79 x\n\nseed-to-soil map:\n50 98 2\n52 50\n\nsoil to fertilizer map:\n0 15 37\n",
+    );
+    assert_eq!(Error::positions(&problems), vec![(1, 11), (5, 1), (7, 1)]);
+}
+#[test]
+fn cyclic_maps_are_reported() {
+    let input = "seeds: 79\n\nseed-to-soil map:\n50 98 2\n\nsoil-to-seed map:\n0 15 37\n";
+    assert_eq!(Error::positions(&validate(input)), vec![(6, 9)]);
+    let error = part1(input).unwrap_err();
+    assert_eq!((error.line, error.column), (6, 9));
+}
 
-humidity-to-location map:
-60 56 37
-56 93 4
-";
-    assert_eq!(part1(input), "35");
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    let almanac = parse_almanac(input)?;
+    almanac
+        .seeds
+        .iter()
+        .map(|&seed| {
+            let path = almanac.locate(input, seed)?;
+            Ok(path.last().map_or(seed, |&(_, location)| location))
+        })
+        .collect::<Result<Vec<usize>, Error>>()?
+        .into_iter()
+        .min()
+        .map(Answer::from)
+        .ok_or_else(|| no_seeds(input))
+}
+#[test]
+fn part1_on_sample() {
+    SAMPLES.check("example", common::Part::First, part1);
 }
-pub fn part2(input: &str) -> String {
+pub fn part2(input: &str) -> Result<Answer, Error> {
     fn merge_ranges(mut ranges: Vec<RangeInclusive<usize>>) -> Vec<RangeInclusive<usize>> {
         ranges.sort_by(|a, b| a.start().cmp(b.start()));
         let (mut merged, final_considered) =
@@ -313,127 +391,121 @@ pub fn part2(input: &str) -> String {
                 .collect()
         }
     }
-    fn parse_almanac(input: &str) -> Almanac {
+    fn parse_almanac(input: &str) -> Result<Almanac, Error> {
         input
             .split("\n\n")
-            .fold(Almanac::default(), |mut almanac, next| {
-                let (_, rest) = next.split_once(':').unwrap();
+            .try_fold(Almanac::default(), |mut almanac, next| {
+                let (_, rest) = split_section(input, next)?;
                 if almanac.seeds.is_empty() {
-                    let seed_defs: Vec<_> = rest
+                    let seed_defs = rest
                         .trim()
                         .split(' ')
-                        .map(|s| s.parse::<usize>().unwrap())
-                        .collect();
+                        .map(|s| parse_at(DAY, input, s, "a seed number"))
+                        .collect::<Result<Vec<usize>, Error>>()?;
+                    if seed_defs.len() % 2 != 0 {
+                        return Err(Error::at(
+                            DAY,
+                            input,
+                            rest,
+                            "seeds in pairs of start and length",
+                        ));
+                    }
                     almanac.seeds.extend(
                         seed_defs
                             .as_slice()
                             .chunks(2)
                             .map(|chunk| chunk[0]..=(chunk[0] + chunk[1])),
                     );
-                    almanac
+                    Ok(almanac)
                 } else {
-                    let mut ranges = Vec::new();
-                    for line in rest.trim().split('\n') {
-                        let mut split = line.split_whitespace();
-                        let destination_start: usize = split.next().unwrap().parse().unwrap();
-                        let source_start: usize = split.next().unwrap().parse().unwrap();
-                        let count: usize = split.next().unwrap().parse().unwrap();
-                        ranges.push(Mapping {
-                            destination_start,
-                            source_start,
-                            count,
-                        });
-                    }
+                    let ranges = rest
+                        .trim()
+                        .split('\n')
+                        .map(|line| parse_mapping(input, line))
+                        .collect::<Result<Vec<_>, Error>>()?;
                     almanac.mappings.push(ranges);
-                    almanac
+                    Ok(almanac)
                 }
             })
     }
-    let almanac = parse_almanac(input);
-    format!(
-        "{}",
-        almanac
-            .map_ranges()
-            .iter()
-            .map(|range| *range.start())
-            .min()
-            .unwrap()
-    )
+    let almanac = parse_almanac(input)?;
+    almanac
+        .map_ranges()
+        .iter()
+        .map(|range| *range.start())
+        .min()
+        .map(Answer::from)
+        .ok_or_else(|| no_seeds(input))
 }
 
 #[test]
-fn part2_on_single() {
-    let input = "seeds: 82 1
+fn short_mapping_line_is_an_error() {
+    let input = "seeds: 79 14
 
 seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
+50 98
 ";
-    assert_eq!(part2(input), "46");
+    let error = part1(input).unwrap_err();
+    assert_eq!(
+        (error.line, error.expected.as_str()),
+        (4, "3 numbers, missing the count")
+    );
+}
+#[test]
+fn part2_on_single() {
+    SAMPLES.check("single_seed", common::Part::Second, part2);
 }
 #[test]
 fn part2_on_sample() {
-    let input = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+    SAMPLES.check("example", common::Part::Second, part2);
+}
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+fn map_seed(input: &str, args: &[&str]) -> Inspection {
+    let [seed] = args else {
+        return Err("expected a seed number".into());
+    };
+    let almanac = parse_almanac(input)?;
+    Ok(almanac
+        .locate(input, seed.parse()?)?
+        .iter()
+        .map(|(category, value)| format!("{} {}", category, value))
+        .collect::<Vec<_>>()
+        .join(" -> "))
+}
+#[test]
+fn seed_79_is_mapped_through_every_category() {
+    assert_eq!(
+        map_seed(SAMPLES.input("example").unwrap(), &["79"]).unwrap(),
+        "seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78 -> location 82"
+    );
+}
 
-humidity-to-location map:
-60 56 37
-56 93 4
-";
-    assert_eq!(part2(input), "46");
+pub struct Day5;
+impl Solution for Day5 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn inspectors(&self) -> &'static [Inspector] {
+        &[Inspector {
+            command: "map seed",
+            args: "<seed>",
+            help: "follow a seed through every map to its location",
+            run: map_seed,
+        }]
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
@@ -1,21 +1,31 @@
+use common::{parse_at, samples, Answer, Error, Samples, Solution};
+
+const DAY: u8 = 6;
+const SAMPLES: Samples = samples!("example");
+
 #[derive(Debug)]
 struct Race {
     time: usize,
     distance_record: u64,
 }
 impl Race {
+    /// Whether holding the button for half the race, which goes furthest, beats the record.
+    fn can_be_beaten(&self) -> bool {
+        // widened, as the products of long races overflow 64 bits
+        let hold = self.time as u128 / 2;
+        hold * (self.time as u128 - hold) > u128::from(self.distance_record)
+    }
+    /// Only meaningful for a race that [`Race::can_be_beaten`].
     pub fn ways_to_beat_record(&self) -> u64 {
-        let t_min = 0.5
-            * (self.time as f32
-                - ((self.time.pow(2) as u64 - 4 * self.distance_record) as f32).sqrt());
+        let discriminant = (self.time as u128).pow(2) - 4 * u128::from(self.distance_record);
+        let root = (discriminant as f64).sqrt();
+        let t_min = 0.5 * (self.time as f64 - root);
         let t_min = if t_min.ceil() == t_min {
             t_min as u64 + 1
         } else {
             t_min.ceil() as u64
         };
-        let t_max = 0.5
-            * (self.time as f32
-                + ((self.time.pow(2) as u64 - 4 * self.distance_record) as f32).sqrt());
+        let t_max = 0.5 * (self.time as f64 + root);
         let t_max = if t_max.floor() == t_max {
             t_max as u64 - 1
         } else {
@@ -24,70 +34,185 @@ impl Race {
         t_max - t_min + 1
     }
 }
-fn parse_numbers<T: std::str::FromStr>(line: &str) -> impl Iterator<Item = T> + '_
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
+fn parse_numbers<'a, T: std::str::FromStr>(
+    input: &'a str,
+    line: &'a str,
+) -> impl Iterator<Item = Result<T, Error>> + 'a {
     line.split_whitespace()
         .skip(1)
-        .map(|s| s.parse::<T>().unwrap())
+        .map(|s| parse_at(DAY, input, s, "a number"))
+}
+/// Splits the input into its "Time:" and "Distance:" lines.
+fn split_lines(input: &str) -> Result<(&str, &str), Error> {
+    input.trim().split_once('\n').ok_or_else(|| {
+        Error::at(
+            DAY,
+            input,
+            &input[input.len()..],
+            "a line of distances after the line of times",
+        )
+    })
 }
-fn parse_races(input: &str) -> impl Iterator<Item = Race> + '_ {
-    let (times, distances) = input.trim().split_once('\n').unwrap();
-    let times = parse_numbers(times);
-    let distance_records = parse_numbers(distances);
+/// Checks the race can be won, pointing at its record if not.
+fn beatable(input: &str, race: Race, record: &str) -> Result<Race, Error> {
+    if race.can_be_beaten() {
+        Ok(race)
+    } else {
+        Err(Error::at(
+            DAY,
+            input,
+            record,
+            format!("a record a {} ms race can beat", race.time),
+        ))
+    }
+}
+fn parse_races(input: &str) -> Result<Vec<Race>, Error> {
+    let (times, distances) = split_lines(input)?;
+    let times = parse_numbers(input, times);
+    let distance_records = parse_numbers(input, distances);
     times
         .zip(distance_records)
-        .map(|(time, distance_record)| Race {
-            time,
-            distance_record,
+        .zip(distances.split_whitespace().skip(1))
+        .map(|((time, distance_record), record)| {
+            let race = Race {
+                time: time?,
+                distance_record: distance_record?,
+            };
+            beatable(input, race, record)
         })
+        .collect()
 }
-pub fn part1(input: &str) -> String {
-    format!(
-        "{}",
-        parse_races(input)
-            .map(|race| race.ways_to_beat_record())
-            .product::<u64>()
-    )
+/// Both lines should be labelled and hold a distance for every time, and every race should
+/// be winnable, on its own and as one long race.
+pub fn validate(input: &str) -> Vec<Error> {
+    let (times, distances) = match split_lines(input) {
+        Ok(lines) => lines,
+        Err(problem) => return vec![problem],
+    };
+    let mut problems = Vec::new();
+    let mut counts = [0; 2];
+    for ((line, label), count) in [(times, "Time:"), (distances, "Distance:")]
+        .into_iter()
+        .zip(counts.iter_mut())
+    {
+        if !line.starts_with(label) {
+            problems.push(Error::at(
+                DAY,
+                input,
+                line,
+                format!("'{}' at the start", label),
+            ));
+        }
+        for number in parse_numbers::<u64>(input, line) {
+            match number {
+                Ok(_) => *count += 1,
+                Err(problem) => problems.push(problem),
+            }
+        }
+    }
+    if counts[0] != counts[1] {
+        problems.push(Error::at(
+            DAY,
+            input,
+            distances,
+            format!("{} distances, one per time, not {}", counts[0], counts[1]),
+        ));
+    }
+    let races = parse_numbers(input, times).zip(parse_numbers(input, distances));
+    for ((time, distance_record), record) in races.zip(distances.split_whitespace().skip(1)) {
+        if let (Ok(time), Ok(distance_record)) = (time, distance_record) {
+            let race = Race {
+                time,
+                distance_record,
+            };
+            problems.extend(beatable(input, race, record).err());
+        }
+    }
+    if problems.is_empty() {
+        problems.extend(parse_race(input).err());
+    }
+    problems
+}
+#[test]
+fn mismatched_lines_are_reported() {
+    let problems = validate("Time: 7 15 30\nDist: 9 40 x\n");
+    assert_eq!(Error::positions(&problems), vec![(2, 1), (2, 12), (2, 1)]);
+}
+#[test]
+fn unbeatable_records_are_reported() {
+    let input = "Time: 3 7\nDistance: 100 9\n";
+    let problems = validate(input);
+    assert_eq!(Error::positions(&problems), vec![(2, 11)]);
+    let error = part1(input).unwrap_err();
+    assert_eq!((error.line, error.column), (2, 11));
+    // a tie doesn't beat the record either
+    let error = part2("Time: 4\nDistance: 4\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
+}
+#[test]
+fn long_races_do_not_overflow() {
+    let input = "Time: 99999999999\nDistance: 1\n";
+    assert_eq!(validate(input), vec![]);
+    assert_eq!(part1(input), Ok(Answer::from(99999999998u64)));
+}
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    Ok(parse_races(input)?
+        .iter()
+        .map(|race| race.ways_to_beat_record())
+        .product::<u64>()
+        .into())
 }
 #[test]
 fn part1_on_sample() {
-    let input = "Time:      7  15   30
-Distance:  9  40  200
-";
-    assert_eq!(part1(input), "288");
-}
-fn parse_race(input: &str) -> Race {
-    let (times, distances) = input.trim().split_once('\n').unwrap();
-    let time = parse_number(times);
-    let distance_record = parse_number(distances);
-    Race {
+    SAMPLES.check("example", common::Part::First, part1);
+}
+fn parse_race(input: &str) -> Result<Race, Error> {
+    let (times, distances) = split_lines(input)?;
+    let time = parse_number(input, times)?;
+    let distance_record = parse_number(input, distances)?;
+    let race = Race {
         time,
         distance_record,
-    }
+    };
+    beatable(input, race, distances)
 }
 
-fn parse_number<T: std::str::FromStr>(times: &str) -> T
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
-    times
-        .split_whitespace()
+/// Reads the line as a single number, ignoring the spaces between its digits.
+fn parse_number<T: std::str::FromStr>(input: &str, line: &str) -> Result<T, Error> {
+    line.split_whitespace()
         .skip(1)
         .flat_map(|s| s.chars())
         .collect::<String>()
         .parse::<T>()
-        .unwrap()
+        .map_err(|_| Error::at(DAY, input, line, "a label followed by digits"))
 }
 
-pub fn part2(input: &str) -> String {
-    format!("{}", parse_race(input).ways_to_beat_record())
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    Ok(parse_race(input)?.ways_to_beat_record().into())
 }
 #[test]
 fn part2_on_sample() {
-    let input = "Time:      7  15   30
-Distance:  9  40  200
-";
-    assert_eq!(part2(input), "71503");
+    SAMPLES.check("example", common::Part::Second, part2);
+}
+
+pub struct Day6;
+impl Solution for Day6 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Wait For It"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
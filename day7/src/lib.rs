@@ -1,5 +1,9 @@
+use common::{parse_at, samples, Answer, Error, Samples, Solution};
 use counter::Counter;
 
+const DAY: u8 = 7;
+const SAMPLES: Samples = samples!("example", "extra");
+
 trait CounterExtDay7 {
     fn top_count(&self) -> usize;
 }
@@ -9,18 +13,37 @@ impl<T: Eq + Ord + Clone + std::hash::Hash> CounterExtDay7 for Counter<T> {
     }
 }
 
-trait ParseableAsCard {
-    fn parse_card(c: char) -> Self;
+trait ParseableAsCard: Sized {
+    fn parse_card(c: char) -> Option<Self>;
 }
-fn parse_cards<C: ParseableAsCard>(cards: &str) -> [C; 5] {
-    let mut chars = cards.chars();
-    [
-        C::parse_card(chars.next().unwrap()),
-        C::parse_card(chars.next().unwrap()),
-        C::parse_card(chars.next().unwrap()),
-        C::parse_card(chars.next().unwrap()),
-        C::parse_card(chars.next().unwrap()),
-    ]
+fn parse_cards<C: ParseableAsCard>(input: &str, cards: &str) -> Result<[C; 5], Error> {
+    let mut chars = cards.char_indices();
+    let mut next_card = || match chars.next() {
+        Some((i, c)) => C::parse_card(c)
+            .ok_or_else(|| Error::at(DAY, input, &cards[i..], "a card: one of AKQJT98765432")),
+        None => Err(Error::at(
+            DAY,
+            input,
+            &cards[cards.len()..],
+            "5 cards in the hand",
+        )),
+    };
+    let hand = [
+        next_card()?,
+        next_card()?,
+        next_card()?,
+        next_card()?,
+        next_card()?,
+    ];
+    match chars.next() {
+        Some((i, _)) => Err(Error::at(
+            DAY,
+            input,
+            &cards[i..],
+            "only 5 cards in the hand",
+        )),
+        None => Ok(hand),
+    }
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 enum HandType {
@@ -40,36 +63,91 @@ struct Hand<C: ParseableAsCard> {
 trait ParseableAsHand<C: ParseableAsCard> {
     fn parse_hand(cards: [C; 5]) -> Self;
 }
-fn parse_hands<C: ParseableAsCard>(input: &str) -> Vec<(Hand<C>, u64)>
+/// Each line holding a hand, as both parsing and validation see them.
+fn hand_lines(input: &str) -> impl Iterator<Item = &str> {
+    input.trim().split('\n')
+}
+fn parse_hands<C: ParseableAsCard>(input: &str) -> Result<Vec<(Hand<C>, u64)>, Error>
 where
     Hand<C>: ParseableAsHand<C>,
 {
-    input
-        .trim()
-        .split('\n')
-        .map(|line| line.split_once(' ').unwrap())
-        .map(|(cards, winnings)| {
-            (
-                Hand::parse_hand(parse_cards(cards)),
-                winnings.parse().unwrap(),
-            )
+    hand_lines(input)
+        .map(|line| {
+            line.split_once(' ')
+                .ok_or_else(|| Error::at(DAY, input, line, "a hand, a space, then a bid"))
+        })
+        .map(|split| {
+            let (cards, winnings) = split?;
+            Ok((
+                Hand::parse_hand(parse_cards(input, cards)?),
+                parse_at(DAY, input, winnings, "a bid")?,
+            ))
         })
         .collect()
 }
-fn solve_part<C: ParseableAsCard + Ord>(input: &str) -> u64
+fn solve_part<C: ParseableAsCard + Ord>(input: &str) -> Result<u64, Error>
 where
     Hand<C>: ParseableAsHand<C>,
 {
-    let mut hands = parse_hands(input);
+    let mut hands = parse_hands(input)?;
     hands.sort_by(|(a_hand, _), (b_hand, _)| a_hand.cmp(b_hand));
-    hands
+    Ok(hands
         .iter()
         .enumerate()
         .map(|(rank, (_, winnings))| (rank as u64 + 1) * winnings)
-        .sum()
+        .sum())
+}
+
+/// Every hand should be exactly five known cards, followed by a bid.
+pub fn validate(input: &str) -> Vec<Error> {
+    let mut problems = Vec::new();
+    for line in hand_lines(input) {
+        let Some((cards, bid)) = line.split_once(' ') else {
+            problems.push(Error::at(DAY, input, line, "a hand, a space, then a bid"));
+            continue;
+        };
+        for (i, c) in cards.char_indices() {
+            if !"AKQJT98765432".contains(c) {
+                problems.push(Error::at(
+                    DAY,
+                    input,
+                    &cards[i..],
+                    "a card: one of AKQJT98765432",
+                ));
+            }
+        }
+        let hand_length = cards.chars().count();
+        if hand_length != 5 {
+            problems.push(Error::at(
+                DAY,
+                input,
+                cards,
+                format!("5 cards in the hand, not {}", hand_length),
+            ));
+        }
+        if let Err(problem) = parse_at::<u64>(DAY, input, bid, "a bid") {
+            problems.push(problem);
+        }
+    }
+    problems
+}
+#[test]
+fn every_bad_hand_is_reported() {
+    let problems = validate("32T3K 765\nT55J 684\nKK6X77 28\nKTJJT x\n");
+    assert_eq!(
+        Error::positions(&problems),
+        vec![(2, 1), (3, 4), (3, 1), (4, 7)]
+    );
+}
+#[test]
+fn validation_splits_lines_like_parsing() {
+    for input in ["32T3K 765\r\nT55J5 684\r\n", "", "32T3K 765\n\nT55J5 684\n"] {
+        let error = part1(input).unwrap_err();
+        assert_eq!(validate(input).first(), Some(&error));
+    }
 }
 
-pub fn part1(input: &str) -> String {
+pub fn part1(input: &str) -> Result<Answer, Error> {
     #[derive(Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Copy, Clone)]
     enum Card {
         Two,
@@ -88,8 +166,8 @@ pub fn part1(input: &str) -> String {
     }
 
     impl ParseableAsCard for Card {
-        fn parse_card(c: char) -> Card {
-            match c {
+        fn parse_card(c: char) -> Option<Card> {
+            Some(match c {
                 'A' => Card::Ace,
                 'K' => Card::King,
                 'Q' => Card::Queen,
@@ -103,8 +181,8 @@ pub fn part1(input: &str) -> String {
                 '4' => Card::Four,
                 '3' => Card::Three,
                 '2' => Card::Two,
-                _ => panic!("unknown char for card encountered: {}", c),
-            }
+                _ => return None,
+            })
         }
     }
 
@@ -137,10 +215,10 @@ pub fn part1(input: &str) -> String {
         }
     }
 
-    format!("{}", solve_part::<Card>(input))
+    solve_part::<Card>(input).map(Answer::from)
 }
 
-pub fn part2(input: &str) -> String {
+pub fn part2(input: &str) -> Result<Answer, Error> {
     #[derive(Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Copy, Clone)]
     enum Card {
         Joker,
@@ -159,8 +237,8 @@ pub fn part2(input: &str) -> String {
     }
 
     impl ParseableAsCard for Card {
-        fn parse_card(c: char) -> Card {
-            match c {
+        fn parse_card(c: char) -> Option<Card> {
+            Some(match c {
                 'A' => Card::Ace,
                 'K' => Card::King,
                 'Q' => Card::Queen,
@@ -174,8 +252,8 @@ pub fn part2(input: &str) -> String {
                 '3' => Card::Three,
                 '2' => Card::Two,
                 'J' => Card::Joker,
-                _ => panic!("unknown char for card encountered: {}", c),
-            }
+                _ => return None,
+            })
         }
     }
     impl ParseableAsHand<Card> for Hand<Card> {
@@ -220,46 +298,47 @@ pub fn part2(input: &str) -> String {
         }
     }
 
-    format!("{}", solve_part::<Card>(input))
+    solve_part::<Card>(input).map(Answer::from)
 }
 
 #[test]
 fn part1_on_sample() {
-    let input = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483
-";
-    assert_eq!(part1(input), "6440");
+    SAMPLES.check("example", common::Part::First, part1);
 }
 #[test]
 fn part1_on_extra_sample() {
-    let input = "AAAAA 2
-22222 3
-AAAAK 5
-22223 7
-AAAKK 11
-22233 13
-AAAKQ 17
-22234 19
-AAKKQ 23
-22334 29
-AAKQJ 31
-22345 37
-AKQJT 41
-23456 43
-";
-    assert_eq!(part1(input), "1343");
+    SAMPLES.check("extra", common::Part::First, part1);
+}
+
+#[test]
+fn unknown_card_is_an_error() {
+    let error = part1("32T3K 765\nT55X5 684\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 4));
 }
 
 #[test]
 fn part2_on_sample() {
-    let input = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483
-";
-    assert_eq!(part2(input), "5905");
+    SAMPLES.check("example", common::Part::Second, part2);
+}
+
+pub struct Day7;
+impl Solution for Day7 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Camel Cards"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
@@ -1,21 +1,123 @@
+use common::{samples, Answer, Error, Inspection, Inspector, Samples, Solution};
 use std::collections::BTreeMap;
 
-fn parse_mappings(lines: &str) -> BTreeMap<&str, (&str, &str)> {
-    lines
+const DAY: u8 = 8;
+const SAMPLES: Samples = samples!("ghosts");
+
+/// Each node's left and right neighbours
+type Network<'a> = BTreeMap<&'a str, (&'a str, &'a str)>;
+
+fn parse_mappings<'a>(input: &'a str, lines: &'a str) -> Result<Network<'a>, Error> {
+    let mappings = lines
         .trim_start()
         .split_terminator('\n')
-        .map(|line| line.split_once(" = ").unwrap())
-        .map(|(current_node, left_right)| {
-            (
-                current_node,
-                left_right
-                    .trim_matches(&['(', ')'][..])
-                    .split_once(", ")
-                    .unwrap(),
+        .map(|line| parse_mapping(input, line))
+        .collect::<Result<BTreeMap<_, _>, Error>>()?;
+    // check every node can be followed, so walking the network can't get stuck
+    if let Some(problem) = dangling_targets(input, &mappings).next() {
+        return Err(problem);
+    }
+    Ok(mappings)
+}
+fn parse_mapping<'a>(input: &str, line: &'a str) -> Result<(&'a str, (&'a str, &'a str)), Error> {
+    let (current_node, left_right) = line
+        .split_once(" = ")
+        .ok_or_else(|| Error::at(DAY, input, line, "'<node> = (<left>, <right>)'"))?;
+    let left_right = left_right
+        .trim_matches(&['(', ')'][..])
+        .split_once(", ")
+        .ok_or_else(|| Error::at(DAY, input, left_right, "'(<left>, <right>)'"))?;
+    Ok((current_node, left_right))
+}
+fn dangling_targets<'a>(
+    input: &'a str,
+    mappings: &'a Network<'_>,
+) -> impl Iterator<Item = Error> + 'a {
+    mappings
+        .values()
+        .flat_map(|(left, right)| [left, right])
+        .filter(|target| !mappings.contains_key(*target))
+        .map(|target| {
+            Error::at(
+                DAY,
+                input,
+                target,
+                format!("a node with a mapping, but '{}' has none", target),
             )
         })
+}
+fn parse_instructions(input: &str, pattern: &str) -> Result<Vec<Instruction>, Error> {
+    if pattern.is_empty() {
+        return Err(Error::at(DAY, input, pattern, "at least one instruction"));
+    }
+    pattern
+        .char_indices()
+        .map(|(i, c)| {
+            c.try_into()
+                .map_err(|_| Error::at(DAY, input, &pattern[i..], "'L' or 'R'"))
+        })
         .collect()
 }
+fn parse_network(input: &str) -> Result<(Vec<Instruction>, Network<'_>), Error> {
+    let (pattern, mappings) = input.split_once("\n\n").ok_or_else(|| {
+        Error::at(
+            DAY,
+            input,
+            &input[input.len()..],
+            "a blank line between the instructions and the network",
+        )
+    })?;
+    Ok((
+        parse_instructions(input, pattern)?,
+        parse_mappings(input, mappings)?,
+    ))
+}
+/// Reports every bad instruction, malformed node and node that leads nowhere, and every
+/// start whose walk never ends.
+pub fn validate(input: &str) -> Vec<Error> {
+    let Some((pattern, lines)) = input.split_once("\n\n") else {
+        return parse_network(input).err().into_iter().collect();
+    };
+    let mut problems = Vec::new();
+    if let Err(problem) = parse_instructions(input, pattern) {
+        problems.push(problem);
+    }
+    let mut mappings = Network::new();
+    for line in lines.trim_start().split_terminator('\n') {
+        match parse_mapping(input, line) {
+            Ok((node, left_right)) => {
+                mappings.insert(node, left_right);
+            }
+            Err(problem) => problems.push(problem),
+        }
+    }
+    problems.extend(dangling_targets(input, &mappings));
+    // only a network that parses can be walked
+    if let (true, Ok(instructions)) = (problems.is_empty(), parse_instructions(input, pattern)) {
+        if let Some((&start, _)) = mappings.get_key_value("AAA") {
+            problems.extend(
+                steps_to_end(input, &instructions, &mappings, start, is_zzz, "'ZZZ'").err(),
+            );
+        }
+        for start in ghost_starts(&mappings) {
+            let goal = "a node ending in 'Z'";
+            problems.extend(
+                steps_to_end(input, &instructions, &mappings, start, is_ghost_end, goal).err(),
+            );
+        }
+    }
+    problems
+}
+#[test]
+fn every_broken_node_is_reported() {
+    let problems = validate("LXR\n\nAAA = (ZZZ, ZZZ)\nBBB (AAA, ZZZ)\nZZZ = (ZZZ, CCC)\n");
+    assert_eq!(Error::positions(&problems), vec![(1, 2), (4, 1), (5, 13)]);
+}
+#[test]
+fn walks_that_never_end_are_reported() {
+    let problems = validate("L\n\nAAA = (AAA, ZZZ)\n11A = (11A, 11A)\nZZZ = (ZZZ, ZZZ)\n");
+    assert_eq!(Error::positions(&problems), vec![(3, 1), (4, 1), (3, 1)]);
+}
 enum Instruction {
     Left,
     Right,
@@ -31,102 +133,82 @@ impl TryFrom<char> for Instruction {
         }
     }
 }
-pub fn part1(input: &str) -> String {
-    let (pattern, mappings) = input.split_once("\n\n").unwrap();
-    let mappings = parse_mappings(mappings);
-    let instructions = pattern.chars().map(|c| c.try_into().unwrap());
+/// How many steps following `instructions` from `start` takes to first reach a node that
+/// `is_end` accepts, or an error at `start` if it never does.
+fn steps_to_end(
+    input: &str,
+    instructions: &[Instruction],
+    mappings: &Network<'_>,
+    start: &str,
+    is_end: fn(&str) -> bool,
+    goal: &str,
+) -> Result<usize, Error> {
+    // by the time every (node, instruction) pair has come up, the walk is going in circles
+    let limit = mappings.len() * instructions.len();
+    let mut node = start;
+    for (step, instruction) in instructions.iter().cycle().take(limit).enumerate() {
+        let (left, right) = mappings[node];
+        node = match instruction {
+            Instruction::Left => left,
+            Instruction::Right => right,
+        };
+        if is_end(node) {
+            return Ok(step + 1);
+        }
+    }
+    Err(Error::at(
+        DAY,
+        input,
+        start,
+        format!("a node the instructions lead from to {}", goal),
+    ))
+}
+fn is_zzz(node: &str) -> bool {
+    node == "ZZZ"
+}
+fn is_ghost_end(node: &str) -> bool {
+    node.ends_with('Z')
+}
+fn ghost_starts<'a>(mappings: &'a Network<'_>) -> impl Iterator<Item = &'a str> {
+    mappings.keys().copied().filter(|node| node.ends_with('A'))
+}
 
-    #[derive(Copy, Clone)]
-    enum NodeState<NodeType> {
-        SearchingForEndState(NodeType),
-        Ended(usize),
-    }
-    format!(
-        "{}",
-        instructions
-            .cycle()
-            .enumerate()
-            .scan(
-                NodeState::SearchingForEndState("AAA"),
-                |accum, (count, instruction)| {
-                    if let NodeState::SearchingForEndState(node) = accum {
-                        match mappings
-                            .get(node)
-                            .map(|(left, right)| *match instruction {
-                                Instruction::Right => right,
-                                Instruction::Left => left,
-                            })
-                            .unwrap()
-                        {
-                            "ZZZ" => *accum = NodeState::Ended(count),
-                            next_node => *node = next_node,
-                        }
-                    }
-                    // needed to derive Copy to do this, so not sure we're actually saving
-                    // on memory use compared to an externally-allocated mutable accumulator...
-                    // (at least it's still on the stack and not the heap)
-                    Some(*accum)
-                },
-            )
-            .find_map(|state| match state {
-                NodeState::Ended(count) => Some(count),
-                NodeState::SearchingForEndState(_) => None,
-            })
-            .unwrap()
-    )
+pub fn part1(input: &str) -> Result<Answer, Error> {
+    let (instructions, mappings) = parse_network(input)?;
+    let (&start, _) = mappings
+        .get_key_value("AAA")
+        .ok_or_else(|| Error::at(DAY, input, input, "a mapping for the starting node 'AAA'"))?;
+    steps_to_end(input, &instructions, &mappings, start, is_zzz, "'ZZZ'").map(Answer::from)
+}
+#[test]
+fn part1_counts_steps() {
+    let input = "RL\n\nAAA = (BBB, CCC)\nBBB = (DDD, EEE)\nCCC = (ZZZ, GGG)\nDDD = (DDD, DDD)\n\
+                 EEE = (EEE, EEE)\nGGG = (GGG, GGG)\nZZZ = (ZZZ, ZZZ)\n";
+    assert_eq!(part1(input), Ok(Answer::from(2usize)));
 }
 
-pub fn part2(input: &str) -> String {
-    let (pattern, mappings) = input.split_once("\n\n").unwrap();
-    let mappings = parse_mappings(mappings);
-    let instructions = pattern.chars().map(|c| c.try_into().unwrap());
-    #[derive(Copy, Clone)]
-    enum NodeState {
-        EndsWithZAfter(usize),
-        // nodes traversed until now
-        SearchingForEndState(usize),
-    }
-    let mut current_nodes = mappings
-        .iter()
-        .filter_map(|(node, (_, _))| {
-            node.ends_with('A')
-                .then_some((*node, NodeState::SearchingForEndState(0)))
+pub fn part2(input: &str) -> Result<Answer, Error> {
+    let (instructions, mappings) = parse_network(input)?;
+    let steps = ghost_starts(&mappings)
+        .map(|start| {
+            steps_to_end(
+                input,
+                &instructions,
+                &mappings,
+                start,
+                is_ghost_end,
+                "a node ending in 'Z'",
+            )
         })
-        .collect::<Vec<_>>();
-    for instruction in instructions.cycle() {
-        if current_nodes
-            .iter()
-            .all(|(_, state)| matches!(state, NodeState::EndsWithZAfter(_)))
-        {
-            break;
-        }
-        for (node_def, state) in current_nodes.iter_mut() {
-            if let NodeState::SearchingForEndState(traversed) = state {
-                let (left, right) = mappings.get(node_def).unwrap();
-                *node_def = match instruction {
-                    Instruction::Right => right,
-                    Instruction::Left => left,
-                };
-                *traversed += 1;
-                if node_def.ends_with('Z') {
-                    *state = NodeState::EndsWithZAfter(*traversed)
-                }
-            }
-        }
-    }
-    format!(
-        "{}",
-        current_nodes
-            .iter()
-            .filter_map(|(_, state)| {
-                if let NodeState::EndsWithZAfter(traversed) = state {
-                    Some(traversed)
-                } else {
-                    None
-                }
-            })
-            .fold(1, |accum, next| lcm(accum, *next))
-    )
+        .try_fold(1, |accum, steps| Ok::<_, Error>(lcm(accum, steps?)))?;
+    Ok(steps.into())
+}
+#[test]
+fn unreachable_ends_are_errors() {
+    let error = part1("L\n\nAAA = (AAA, AAA)").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 1));
+    let error = part2("L\n\n11A = (11A, 11A)").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 1));
 }
 /// thank you <https://rustp.org/number-theory/lcm/>
 fn gcd(mut a: usize, mut b: usize) -> usize {
@@ -150,17 +232,58 @@ fn lcm(a: usize, b: usize) -> usize {
 }
 
 #[test]
-fn part2_on_sample() {
+fn dangling_node_is_an_error() {
     let input = "LR
 
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)
+AAA = (BBB, ZZZ)
+ZZZ = (ZZZ, ZZZ)
 ";
-    assert_eq!(part2(input), "6");
+    let error = part1(input).unwrap_err();
+    assert_eq!((error.line, error.column), (3, 8));
+}
+
+#[test]
+fn part2_on_sample() {
+    SAMPLES.check("ghosts", common::Part::Second, part2);
+}
+
+fn show_node(input: &str, args: &[&str]) -> Inspection {
+    let [node] = args else {
+        return Err("expected a node name".into());
+    };
+    let (_, mappings) = parse_network(input)?;
+    let (left, right) = mappings
+        .get(node)
+        .ok_or_else(|| format!("there's no node '{}'", node))?;
+    Ok(format!("{} = ({}, {})", node, left, right))
+}
+
+pub struct Day8;
+impl Solution for Day8 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn inspectors(&self) -> &'static [Inspector] {
+        &[Inspector {
+            command: "node",
+            args: "<name>",
+            help: "where the node leads left and right",
+            run: show_node,
+        }]
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
@@ -1,5 +1,9 @@
+use common::{parse_at, samples, Answer, Error, Samples, Solution};
 use std::collections::VecDeque;
 
+const DAY: u8 = 9;
+const SAMPLES: Samples = samples!("first_line", "second_line", "example", "third_line");
+
 trait VecAndVecDequeExtForDay9 {
     fn compute_diffs(&self) -> VecDeque<i64>;
 }
@@ -53,88 +57,145 @@ macro_rules! complete_numbers {
         };
     }
 
-fn parse_numbers(input: &str) -> impl Iterator<Item = Vec<i64>> + '_ {
+fn parse_numbers(input: &str) -> impl Iterator<Item = Result<Vec<i64>, Error>> + '_ {
     input.trim().split('\n').map(|line| {
-        line.split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect::<Vec<_>>()
+        let numbers = line
+            .split_whitespace()
+            .map(|s| parse_at(DAY, input, s, "a number"))
+            .collect::<Result<Vec<i64>, Error>>()?;
+        if numbers.len() < 2 {
+            return Err(Error::at(
+                DAY,
+                input,
+                line,
+                "at least 2 numbers in the history",
+            ));
+        }
+        if !differences_reach_zero(&numbers) {
+            return Err(Error::at(
+                DAY,
+                input,
+                line,
+                "a history whose differences reach all zeros",
+            ));
+        }
+        Ok(numbers)
     })
 }
-pub fn part1(input: &str) -> String {
+/// Whether differencing `numbers` over and over ends in a row of zeros before it runs out
+/// of values, which extrapolating needs.
+fn differences_reach_zero(numbers: &[i64]) -> bool {
+    let mut row = numbers.to_vec();
+    while !row.is_empty() {
+        if row.iter().all(|&n| n == 0) {
+            return true;
+        }
+        row = row.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    }
+    false
+}
+pub fn validate(input: &str) -> Vec<Error> {
+    parse_numbers(input).filter_map(Result::err).collect()
+}
+#[test]
+fn every_short_or_garbled_history_is_reported() {
+    let problems = validate("0 3 6\n1\n10 x 16\n");
+    assert_eq!(Error::positions(&problems), vec![(2, 1), (3, 4)]);
+}
+#[test]
+fn histories_that_never_level_out_are_reported() {
+    let problems = validate("0 3 6\n22222 3\n1 2 4\n");
+    assert_eq!(Error::positions(&problems), vec![(2, 1), (3, 1)]);
+    let error = part2("1 2 4\n").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 1));
+}
+pub fn part1(input: &str) -> Result<Answer, Error> {
     use std::ops::Add;
-    format!(
-        "{}",
-        parse_numbers(input)
-            .map(|numbers| {
-                let mut diff_record = vec![numbers.compute_diffs()];
-                compute_diffs!(diff_record);
-                let accessor = VecDeque::back;
-                let mutator = VecDeque::push_back;
-                let completer: fn(i64, i64) -> <i64 as Add<i64>>::Output = i64::add;
-                let missing_diff_val = complete_numbers!(diff_record, accessor, mutator, completer);
-                numbers.last().unwrap() + missing_diff_val
-            })
-            .sum::<i64>()
-    )
+    parse_numbers(input)
+        .map(|numbers| {
+            let numbers = numbers?;
+            let mut diff_record = vec![numbers.compute_diffs()];
+            compute_diffs!(diff_record);
+            let accessor = VecDeque::back;
+            let mutator = VecDeque::push_back;
+            let completer: fn(i64, i64) -> <i64 as Add<i64>>::Output = i64::add;
+            let missing_diff_val = complete_numbers!(diff_record, accessor, mutator, completer);
+            Ok(numbers.last().unwrap() + missing_diff_val)
+        })
+        .sum::<Result<i64, Error>>()
+        .map(Answer::from)
 }
 
 #[test]
 fn part1_on_first_sample_line() {
-    let input = "0 3 6 9 12 15\n";
-    assert_eq!(part1(input), "18");
+    SAMPLES.check("first_line", common::Part::First, part1);
 }
 #[test]
 fn part1_on_second_sample_line() {
-    let input = "1 3 6 10 15 21\n";
-    assert_eq!(part1(input), "28");
+    SAMPLES.check("second_line", common::Part::First, part1);
 }
 #[test]
 fn part1_on_sample() {
-    let input = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45
-";
-    assert_eq!(part1(input), "114");
+    SAMPLES.check("example", common::Part::First, part1);
+}
+#[test]
+fn lone_number_is_an_error() {
+    let error = part1("0 3 6\n7\n").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
 }
 
-pub fn part2(input: &str) -> String {
+pub fn part2(input: &str) -> Result<Answer, Error> {
     use std::ops::Sub;
-    format!(
-        "{}",
-        parse_numbers(input)
-            .map(|numbers| {
-                let mut diff_record = vec![numbers.compute_diffs()];
-                compute_diffs!(diff_record);
-                let accessor = VecDeque::front;
-                let mutator = VecDeque::push_front;
-                let completer: fn(i64, i64) -> <i64 as Sub<i64>>::Output = i64::sub;
-                let missing_diff_val = complete_numbers!(diff_record, accessor, mutator, completer);
-                numbers.first().unwrap() - missing_diff_val
-            })
-            .sum::<i64>()
-    )
+    parse_numbers(input)
+        .map(|numbers| {
+            let numbers = numbers?;
+            let mut diff_record = vec![numbers.compute_diffs()];
+            compute_diffs!(diff_record);
+            let accessor = VecDeque::front;
+            let mutator = VecDeque::push_front;
+            let completer: fn(i64, i64) -> <i64 as Sub<i64>>::Output = i64::sub;
+            let missing_diff_val = complete_numbers!(diff_record, accessor, mutator, completer);
+            Ok(numbers.first().unwrap() - missing_diff_val)
+        })
+        .sum::<Result<i64, Error>>()
+        .map(Answer::from)
 }
 
 #[test]
 fn part2_on_first_sample_line() {
-    let input = "0 3 6 9 12 15\n";
-    assert_eq!(part2(input), "-3");
+    SAMPLES.check("first_line", common::Part::Second, part2);
 }
 #[test]
 fn part2_on_second_sample_line() {
-    let input = "1 3 6 10 15 21\n";
-    assert_eq!(part2(input), "0");
+    SAMPLES.check("second_line", common::Part::Second, part2);
 }
 #[test]
 fn part2_on_third_sample_line() {
-    let input = "10 13 16 21 30 45\n";
-    assert_eq!(part2(input), "5");
+    SAMPLES.check("third_line", common::Part::Second, part2);
 }
 #[test]
 fn part2_on_sample() {
-    let input = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45
-";
-    assert_eq!(part2(input), "2");
+    SAMPLES.check("example", common::Part::Second, part2);
+}
+
+pub struct Day9;
+impl Solution for Day9 {
+    fn day(&self) -> u8 {
+        DAY
+    }
+    fn name(&self) -> &'static str {
+        "Mirage Maintenance"
+    }
+    fn samples(&self) -> &'static Samples {
+        &SAMPLES
+    }
+    fn validate(&self, input: &str) -> Vec<Error> {
+        validate(input)
+    }
+    fn part1(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part1(input))
+    }
+    fn part2(&self, input: &str) -> Option<Result<Answer, Error>> {
+        Some(part2(input))
+    }
 }
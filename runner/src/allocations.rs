@@ -0,0 +1,163 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The system allocator, counting what each thread allocates once [`enable`] is called.
+///
+/// Counts are per thread, so parts running side by side under `--jobs` don't see each
+/// other's allocations. A solution that spawned threads of its own would be under-counted.
+pub struct Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static COUNTERS: Counters = const { Counters::new() };
+}
+
+struct Counters {
+    allocations: Cell<u64>,
+    bytes: Cell<u64>,
+    // signed, since memory allocated before a reset can be freed after it
+    live: Cell<i64>,
+    peak: Cell<i64>,
+}
+impl Counters {
+    const fn new() -> Counters {
+        Counters {
+            allocations: Cell::new(0),
+            bytes: Cell::new(0),
+            live: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    }
+    fn allocated(&self, size: usize) {
+        self.allocations.set(self.allocations.get() + 1);
+        self.bytes.set(self.bytes.get() + size as u64);
+        self.resized(size as i64);
+    }
+    fn resized(&self, change: i64) {
+        let live = self.live.get() + change;
+        self.live.set(live);
+        self.peak.set(self.peak.get().max(live));
+    }
+}
+
+fn record(update: impl FnOnce(&Counters)) {
+    if ENABLED.load(Ordering::Relaxed) {
+        // the counters are already gone while a thread is being torn down
+        let _ = COUNTERS.try_with(update);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(|counters| counters.allocated(layout.size()));
+        System.alloc(layout)
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(|counters| counters.allocated(layout.size()));
+        System.alloc_zeroed(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(|counters| counters.resized(-(layout.size() as i64)));
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // a realloc can move everything, so count it as a fresh allocation of the new size
+        record(|counters| {
+            counters.allocations.set(counters.allocations.get() + 1);
+            counters.bytes.set(counters.bytes.get() + new_size as u64);
+            counters.resized(new_size as i64 - layout.size() as i64);
+        });
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Starts counting; until then the allocator costs no more than the system one.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// What one run of a part allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    /// The total size of every allocation, however short-lived
+    pub bytes: u64,
+    /// The most that was allocated and not yet freed at any one time
+    pub peak: u64,
+}
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            Bytes(self.bytes),
+            Bytes(self.peak)
+        )
+    }
+}
+
+/// Runs `f`, counting what it allocates on this thread.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    COUNTERS.with(|counters| {
+        counters.allocations.set(0);
+        counters.bytes.set(0);
+        counters.live.set(0);
+        counters.peak.set(0);
+    });
+    let result = f();
+    let stats = COUNTERS.with(|counters| AllocStats {
+        allocations: counters.allocations.get(),
+        bytes: counters.bytes.get(),
+        peak: counters.peak.get().max(0) as u64,
+    });
+    (result, stats)
+}
+
+/// A byte count in the largest binary unit that keeps it above 1.
+pub struct Bytes(pub u64);
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut size = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[test]
+fn allocations_are_counted_per_run() {
+    enable();
+    let (_, stats) = measure(|| {
+        let mut kept = Vec::with_capacity(1000);
+        for _ in 0..10 {
+            let scratch = Vec::<u8>::with_capacity(100);
+            kept.push(scratch.capacity());
+        }
+        kept
+    });
+    assert_eq!(
+        stats,
+        AllocStats {
+            allocations: 11,
+            bytes: 1000 * size_of::<usize>() as u64 + 10 * 100,
+            peak: 1000 * size_of::<usize>() as u64 + 100,
+        }
+    );
+}
+#[test]
+fn byte_counts_use_binary_units() {
+    assert_eq!(Bytes(512).to_string(), "512 B");
+    assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+    assert_eq!(Bytes(3 * 1024 * 1024).to_string(), "3.0 MiB");
+}
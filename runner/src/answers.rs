@@ -0,0 +1,154 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use aoc2023::{Answer, Part};
+use toml::{Table, Value};
+
+use crate::report::Outcome;
+
+/// Known-good answers, keyed by day then part, e.g.
+///
+/// ```toml
+/// [day3]
+/// part1 = 4361
+/// part2 = 467835
+/// ```
+///
+/// Answers that aren't integers, or don't fit in TOML's, are kept as strings.
+#[derive(Debug, Default)]
+pub struct Answers(Table);
+impl Answers {
+    /// Reads the answers file at `path`, which is allowed not to exist yet.
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .parse::<Table>()
+                .map(Answers)
+                .map_err(|e| format!("couldn't parse '{}': {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("couldn't read '{}': {}", path.display(), e)),
+        }
+    }
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut days = self.0.iter().collect::<Vec<_>>();
+        days.sort_by_key(|(key, _)| day_from_key(key));
+        let sorted = days
+            .into_iter()
+            .map(|(key, parts)| (key.clone(), parts.clone()))
+            .collect::<Table>();
+        fs::write(path, sorted.to_string())
+    }
+    pub fn expected(&self, day: u8, part: Part) -> Option<Answer> {
+        match self.0.get(&day_key(day))?.get(part_key(part))? {
+            Value::Integer(n) => Some(Answer::from(*n)),
+            Value::String(s) => s.parse().ok(),
+            other => other.to_string().parse().ok(),
+        }
+    }
+    pub fn record(&mut self, day: u8, part: Part, answer: &Answer) {
+        let parts = self
+            .0
+            .entry(day_key(day))
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Value::Table(parts) = parts {
+            let value = match answer.as_integer().map(i64::try_from) {
+                Some(Ok(n)) => Value::Integer(n),
+                _ => Value::String(answer.to_string()),
+            };
+            parts.insert(part_key(part).to_string(), value);
+        }
+    }
+}
+fn day_key(day: u8) -> String {
+    format!("day{}", day)
+}
+fn day_from_key(key: &str) -> Option<u8> {
+    key.strip_prefix("day")?.parse().ok()
+}
+fn part_key(part: Part) -> &'static str {
+    match part {
+        Part::First => "part1",
+        Part::Second => "part2",
+    }
+}
+
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: Answer,
+        actual: Answer,
+    },
+    NoExpectation,
+    Unsolved,
+    /// The solution rejected the input, so there's no answer to compare
+    Errored,
+}
+impl Verdict {
+    pub fn of(expected: Option<Answer>, outcome: &Outcome) -> Verdict {
+        let actual = match outcome {
+            Outcome::Solved(actual) => actual,
+            Outcome::Failed(_) => return Verdict::Errored,
+            Outcome::Unsolved => return Verdict::Unsolved,
+        };
+        match expected {
+            None => Verdict::NoExpectation,
+            Some(expected) if expected == *actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected,
+                actual: actual.clone(),
+            },
+        }
+    }
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Errored)
+    }
+}
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::NoExpectation => write!(f, "no expected answer recorded"),
+            Verdict::Unsolved => write!(f, "not yet solved"),
+            Verdict::Errored => write!(f, "FAIL (the solution returned an error)"),
+            Verdict::Fail { expected, actual } => {
+                // point at the first character where the answers diverge
+                let (expected, actual) = (expected.to_string(), actual.to_string());
+                let diverge_at = expected
+                    .chars()
+                    .zip(actual.chars())
+                    .take_while(|(e, a)| e == a)
+                    .count();
+                writeln!(f, "FAIL")?;
+                writeln!(f, "  expected: {}", expected)?;
+                writeln!(f, "    actual: {}", actual)?;
+                write!(f, "            {}^", " ".repeat(diverge_at))
+            }
+        }
+    }
+}
+
+#[test]
+fn recorded_answers_survive_a_round_trip() {
+    let mut answers = Answers::default();
+    answers.record(10, Part::First, &Answer::from(4usize));
+    answers.record(2, Part::Second, &Answer::from(u64::MAX));
+    answers.record(8, Part::First, &Answer::from("ZZZ"));
+    let reparsed = Answers(answers.0.to_string().parse().unwrap());
+    assert_eq!(reparsed.expected(10, Part::First), Some(Answer::from(4u64)));
+    assert_eq!(
+        reparsed.expected(2, Part::Second),
+        Some(Answer::from(u64::MAX))
+    );
+    assert_eq!(reparsed.expected(8, Part::First), Some(Answer::from("ZZZ")));
+    assert_eq!(reparsed.expected(2, Part::First), None);
+}
+#[test]
+fn mismatch_points_at_first_differing_character() {
+    let verdict = Verdict::Fail {
+        expected: Answer::from(4361usize),
+        actual: Answer::from(4391usize),
+    };
+    assert!(verdict.to_string().ends_with("\n              ^"));
+}
@@ -0,0 +1,395 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aoc2023::Part;
+use serde_json::{json, Value};
+
+use crate::input::InputSource;
+use crate::report::{self, Outcome, PartRun};
+use crate::selection::DaySelection;
+use crate::PartOption;
+
+#[derive(clap::Args, Debug)]
+pub struct BenchArgs {
+    /// A day number, or a range of days such as `3..=7`
+    #[arg(short, long, value_parser = DaySelection::parse, required_unless_present = "all")]
+    day: Option<DaySelection>,
+    /// Benchmark every registered day
+    #[arg(short, long, conflicts_with = "day")]
+    all: bool,
+    #[arg(short, long, default_value_t = PartOption::Both)]
+    part: PartOption,
+    /// How many times to run each part; each run includes parsing the input
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// Directory holding the `dayN` input files
+    #[arg(long, env = "AOC_INPUT_DIR", default_value = "./input")]
+    input_dir: PathBuf,
+    /// Append the timings to the history file, tagged with the checked out commit
+    #[arg(long)]
+    save: bool,
+    /// Compare against the last saved timings, failing if any part got slower than the threshold
+    #[arg(long)]
+    compare: bool,
+    /// How many percent slower a part's median may get before `--compare` flags it
+    #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+    threshold: f64,
+    /// Where `--save` and `--compare` keep past timings
+    #[arg(long, default_value = "./bench-history.jsonl")]
+    history: PathBuf,
+}
+
+/// Benchmarks the selected days one part at a time, so parts don't compete for the CPU,
+/// then compares with and saves to the history as asked.
+pub fn bench(args: &BenchArgs) -> ExitCode {
+    let selection = if args.all {
+        DaySelection::All
+    } else {
+        args.day
+            .clone()
+            .expect("clap requires --day when --all is absent")
+    };
+    let solutions = selection.solutions();
+    if solutions.is_empty() {
+        crate::report_unimplemented(&selection);
+        return ExitCode::FAILURE;
+    }
+    // load before running anything, so a broken history fails fast
+    let history = if args.compare {
+        match History::load(&args.history) {
+            Ok(history) => Some(history),
+            Err(error) => {
+                eprintln!("{}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+    let pool = match rayon::ThreadPoolBuilder::new().num_threads(1).build() {
+        Ok(pool) => pool,
+        Err(error) => {
+            eprintln!("couldn't start the benchmark thread: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+    let mut runs = Vec::new();
+    let mut skipped = Vec::new();
+    for solution in solutions {
+        let source = InputSource::for_day(&args.input_dir, solution.day());
+        match source.read() {
+            Ok(input) => runs.extend(pool.install(|| {
+                crate::run_day(
+                    solution,
+                    args.part.parts(),
+                    &source,
+                    &input,
+                    args.runs,
+                    false,
+                )
+            })),
+            Err(error) if error.is_missing() && !selection.is_single() => {
+                skipped.push(format!("day {}", solution.day()));
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+                failed = true;
+            }
+        }
+    }
+    report::print_lines(&runs, true);
+    if !skipped.is_empty() {
+        println!("\nskipped (no input file): {}", skipped.join(", "));
+    }
+    failed |= runs
+        .iter()
+        .any(|run| matches!(run.outcome, Outcome::Failed(_)));
+
+    if let Some(history) = &history {
+        println!();
+        for run in runs.iter().filter(|run| run.timings.runs() > 0) {
+            match history.baseline(run.day, run.part) {
+                Some(baseline) => {
+                    let comparison = Comparison::new(baseline, run.timings.median());
+                    let regressed = comparison.is_regression(args.threshold);
+                    println!(
+                        "day {}, {}: {}{}",
+                        run.day,
+                        run.part,
+                        comparison,
+                        if regressed { "  SLOWER" } else { "" }
+                    );
+                    failed |= regressed;
+                }
+                None => println!("day {}, {}: no saved baseline", run.day, run.part),
+            }
+        }
+    }
+    if args.save {
+        let commit = git_commit(Path::new(".")).unwrap_or_else(|| "unknown".to_string());
+        let saved_at = now();
+        let entries = runs
+            .iter()
+            .filter_map(|run| Entry::of(run, &commit, saved_at))
+            .collect::<Vec<_>>();
+        match History::append(&args.history, &entries) {
+            Ok(()) => println!(
+                "\nsaved {} timings for {} to '{}'",
+                entries.len(),
+                short_commit(&commit),
+                args.history.display()
+            ),
+            Err(error) => {
+                eprintln!("couldn't write '{}': {}", args.history.display(), error);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// One benchmarked part, as kept in the history file: a JSON object per line, so saving
+/// only ever appends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub day: u8,
+    pub part: Part,
+    /// The commit that was checked out, or "unknown" outside a git checkout
+    pub commit: String,
+    /// Seconds since the Unix epoch
+    pub saved_at: u64,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+impl Entry {
+    /// Solved runs become entries; there's nothing to time for the rest.
+    pub fn of(run: &PartRun, commit: &str, saved_at: u64) -> Option<Entry> {
+        if !matches!(run.outcome, Outcome::Solved(_)) {
+            return None;
+        }
+        let t = &run.timings;
+        Some(Entry {
+            day: run.day,
+            part: run.part,
+            commit: commit.to_string(),
+            saved_at,
+            runs: t.runs(),
+            min: t.min(),
+            median: t.median(),
+            mean: t.mean(),
+            max: t.max(),
+        })
+    }
+    fn to_json(&self) -> Value {
+        json!({
+            "day": self.day,
+            "part": self.part.number(),
+            "commit": self.commit,
+            "saved_at": self.saved_at,
+            "runs": self.runs,
+            "min_ns": self.min.as_nanos() as u64,
+            "median_ns": self.median.as_nanos() as u64,
+            "mean_ns": self.mean.as_nanos() as u64,
+            "max_ns": self.max.as_nanos() as u64,
+        })
+    }
+    fn from_json(value: &Value) -> Option<Entry> {
+        let nanos = |key: &str| value[key].as_u64().map(Duration::from_nanos);
+        Some(Entry {
+            day: u8::try_from(value["day"].as_u64()?).ok()?,
+            part: match value["part"].as_u64()? {
+                1 => Part::First,
+                2 => Part::Second,
+                _ => return None,
+            },
+            commit: value["commit"].as_str()?.to_string(),
+            saved_at: value["saved_at"].as_u64()?,
+            runs: value["runs"].as_u64()? as usize,
+            min: nanos("min_ns")?,
+            median: nanos("median_ns")?,
+            mean: nanos("mean_ns")?,
+            max: nanos("max_ns")?,
+        })
+    }
+}
+
+/// Every entry saved so far, oldest first.
+#[derive(Debug, Default)]
+pub struct History(Vec<Entry>);
+impl History {
+    /// Reads the history file at `path`, which is allowed not to exist yet.
+    pub fn load(path: &Path) -> Result<History, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(History::default()),
+            Err(e) => return Err(format!("couldn't read '{}': {}", path.display(), e)),
+        };
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .ok()
+                    .as_ref()
+                    .and_then(Entry::from_json)
+                    .ok_or_else(|| format!("couldn't parse line {} of '{}'", i + 1, path.display()))
+            })
+            .collect::<Result<_, _>>()
+            .map(History)
+    }
+    pub fn append(path: &Path, entries: &[Entry]) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        for entry in entries {
+            writeln!(file, "{}", entry.to_json())?;
+        }
+        Ok(())
+    }
+    /// The most recently saved entry for a part, to compare new timings against.
+    pub fn baseline(&self, day: u8, part: Part) -> Option<&Entry> {
+        self.0
+            .iter()
+            .rev()
+            .find(|entry| entry.day == day && entry.part == part)
+    }
+}
+
+/// How a part's median time compares with its baseline's.
+pub struct Comparison<'a> {
+    pub baseline: &'a Entry,
+    pub median: Duration,
+    /// How much slower, as a percentage; negative when it got faster
+    pub change: f64,
+}
+impl Comparison<'_> {
+    pub fn new(baseline: &Entry, median: Duration) -> Comparison<'_> {
+        let before = baseline.median.as_secs_f64();
+        let change = if before > 0.0 {
+            (median.as_secs_f64() - before) / before * 100.0
+        } else {
+            0.0
+        };
+        Comparison {
+            baseline,
+            median,
+            change,
+        }
+    }
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change > threshold
+    }
+}
+impl fmt::Display for Comparison<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "median {:.2?} vs {:.2?} at {} ({:+.1}%)",
+            self.median,
+            self.baseline.median,
+            short_commit(&self.baseline.commit),
+            self.change
+        )
+    }
+}
+fn short_commit(commit: &str) -> &str {
+    commit.get(..10).unwrap_or(commit)
+}
+
+/// The commit `HEAD` points at, read straight from the `.git` directory under `root`.
+pub fn git_commit(root: &Path) -> Option<String> {
+    let git = root.join(".git");
+    let head = fs::read_to_string(git.join("HEAD")).ok()?;
+    let Some(reference) = head.trim().strip_prefix("ref: ") else {
+        // a detached HEAD holds the hash itself
+        return Some(head.trim().to_string());
+    };
+    if let Ok(hash) = fs::read_to_string(git.join(reference)) {
+        return Some(hash.trim().to_string());
+    }
+    // refs that haven't changed since the last `git gc` only live in packed-refs
+    fs::read_to_string(git.join("packed-refs"))
+        .ok()?
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|(_, name)| *name == reference)
+        .map(|(hash, _)| hash.to_string())
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+fn entry(day: u8, commit: &str, median_ms: u64) -> Entry {
+    let median = Duration::from_millis(median_ms);
+    Entry {
+        day,
+        part: Part::First,
+        commit: commit.to_string(),
+        saved_at: 1_700_000_000,
+        runs: 10,
+        min: median,
+        median,
+        mean: median,
+        max: median,
+    }
+}
+#[test]
+fn history_survives_a_round_trip() {
+    let path = std::env::temp_dir().join(format!("bench-history-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    History::append(&path, &[entry(5, "abc", 10), entry(11, "abc", 3)]).unwrap();
+    History::append(&path, &[entry(5, "def", 12)]).unwrap();
+    let history = History::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(history.0.len(), 3);
+    assert_eq!(history.baseline(5, Part::First), Some(&entry(5, "def", 12)));
+    assert_eq!(history.baseline(5, Part::Second), None);
+}
+#[test]
+fn slowdowns_past_the_threshold_are_regressions() {
+    let baseline = entry(5, "abc", 100);
+    let slower = Comparison::new(&baseline, Duration::from_millis(115));
+    assert!(slower.is_regression(10.0));
+    assert!(!slower.is_regression(20.0));
+    let faster = Comparison::new(&baseline, Duration::from_millis(50));
+    assert!(!faster.is_regression(10.0));
+    assert_eq!(
+        faster.to_string(),
+        "median 50.00ms vs 100.00ms at abc (-50.0%)"
+    );
+}
+#[test]
+fn commit_is_read_through_the_head_ref() {
+    let root = std::env::temp_dir().join(format!("bench-git-{}", std::process::id()));
+    let heads = root.join(".git/refs/heads");
+    fs::create_dir_all(&heads).unwrap();
+    fs::write(root.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+    fs::write(
+        root.join(".git/packed-refs"),
+        "# pack-refs with: peeled\n0123abcd refs/heads/main\n",
+    )
+    .unwrap();
+    assert_eq!(git_commit(&root).as_deref(), Some("0123abcd"));
+    fs::write(heads.join("main"), "4567cdef\n").unwrap();
+    assert_eq!(git_commit(&root).as_deref(), Some("4567cdef"));
+    fs::write(root.join(".git/HEAD"), "89abef01\n").unwrap();
+    assert_eq!(git_commit(&root).as_deref(), Some("89abef01"));
+    fs::remove_dir_all(&root).unwrap();
+}
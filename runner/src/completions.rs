@@ -0,0 +1,63 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::Args;
+
+/// The runner's command line as the shells see it, with `--day` offering the registered
+/// days rather than any number.
+fn completable() -> clap::Command {
+    let registered =
+        || {
+            PossibleValuesParser::new(aoc2023::all().map(|solution| {
+                PossibleValue::new(solution.day().to_string()).help(solution.name())
+            }))
+        };
+    let mut command = Args::command().mut_arg("day", |arg| arg.value_parser(registered()));
+    // `new` is left alone, since its day is by definition not registered yet
+    for name in ["bench", "repl", "watch"] {
+        command = command.mut_subcommand(name, |subcommand| {
+            subcommand.mut_arg("day", |arg| arg.value_parser(registered()))
+        });
+    }
+    command
+}
+
+pub fn write_completions(shell: Shell, out: &mut impl Write) {
+    let mut command = completable();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, out);
+}
+
+pub fn write_manpage(out: &mut impl Write) -> io::Result<()> {
+    clap_mangen::Man::new(Args::command()).render(out)
+}
+
+/// Writes the page for the runner and one for each subcommand, which it refers to.
+pub fn write_manpages(out_dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(out_dir)?;
+    clap_mangen::generate_to(Args::command(), out_dir)
+}
+
+#[test]
+fn completions_offer_only_registered_days() {
+    let mut script = Vec::new();
+    write_completions(Shell::Fish, &mut script);
+    let script = String::from_utf8(script).unwrap();
+    for solution in aoc2023::all() {
+        assert!(script.contains(&format!("{}\\t'{}'", solution.day(), solution.name())));
+    }
+    assert!(!script.contains("26\\t"));
+}
+#[test]
+fn manpage_covers_every_flag() {
+    let mut page = Vec::new();
+    write_manpage(&mut page).unwrap();
+    let page = String::from_utf8(page).unwrap();
+    assert!(page.contains("\\-\\-profile\\-alloc"));
+    assert!(page.contains("runner\\-bench"));
+}
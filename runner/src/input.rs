@@ -0,0 +1,119 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+    /// One of the day's named samples, embedded in its crate
+    Sample {
+        day: u8,
+        name: String,
+    },
+}
+impl InputSource {
+    /// A path, or `-` for stdin.
+    pub fn parse(s: &str) -> Result<InputSource, Infallible> {
+        Ok(match s {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        })
+    }
+    /// The conventional `dayN` file inside `input_dir`.
+    pub fn for_day(input_dir: &Path, day: u8) -> InputSource {
+        InputSource::File(input_dir.join(format!("day{}", day)))
+    }
+    pub fn read(&self) -> Result<String, InputError> {
+        let read = match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+            InputSource::File(path) => fs::read_to_string(path),
+            InputSource::Sample { day, name } => {
+                let samples = aoc2023::find(*day).map(|solution| solution.samples());
+                match samples.and_then(|samples| samples.input(name)) {
+                    Some(input) => Ok(input.to_string()),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!(
+                            "day {} only has samples: {}",
+                            day,
+                            samples
+                                .map(|samples| samples.names().collect::<Vec<_>>().join(", "))
+                                .unwrap_or_default()
+                        ),
+                    )),
+                }
+            }
+        };
+        read.map_err(|error| InputError {
+            source: self.clone(),
+            error,
+        })
+    }
+}
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Sample { name, .. } => write!(f, "sample {}", name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    source: InputSource,
+    error: io::Error,
+}
+impl InputError {
+    pub fn source(&self) -> &InputSource {
+        &self.source
+    }
+    pub fn is_missing(&self) -> bool {
+        self.error.kind() == io::ErrorKind::NotFound
+    }
+}
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "couldn't read input from '{}': {}",
+            self.source, self.error
+        )
+    }
+}
+
+#[test]
+fn dash_means_stdin() {
+    assert_eq!(InputSource::parse("-"), Ok(InputSource::Stdin));
+    assert_eq!(
+        InputSource::parse("./input/day1"),
+        Ok(InputSource::File(PathBuf::from("./input/day1")))
+    );
+}
+#[test]
+fn missing_file_is_reported_as_missing() {
+    let error = InputSource::for_day(Path::new("./does-not-exist"), 1)
+        .read()
+        .unwrap_err();
+    assert!(error.is_missing());
+    assert!(error.to_string().contains("does-not-exist"));
+}
+#[test]
+fn unknown_sample_lists_the_known_ones() {
+    let error = InputSource::Sample {
+        day: 7,
+        name: "nope".to_string(),
+    }
+    .read()
+    .unwrap_err();
+    assert!(error.is_missing());
+    assert!(error.to_string().contains("example, extra"));
+}
@@ -1,10 +1,36 @@
 use std::fmt;
-use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
 
-use clap::{Parser, ValueEnum};
+use answers::{Answers, Verdict};
+use aoc2023::{Part, Solution};
+use clap::{Parser, Subcommand, ValueEnum};
+use input::InputSource;
+use rayon::prelude::*;
+use report::{InputFailure, Outcome, PartRun};
+use selection::DaySelection;
+use timing::Timings;
+
+mod allocations;
+mod answers;
+mod bench;
+mod completions;
+mod input;
+mod repl;
+mod report;
+mod scaffold;
+mod selection;
+#[cfg(feature = "serve")]
+mod serve;
+mod timing;
+mod watch;
+
+#[global_allocator]
+static ALLOCATOR: allocations::Counting = allocations::Counting;
 
 #[derive(Debug, Clone, Default, ValueEnum)]
-enum PartOption {
+pub enum PartOption {
     First,
     Second,
     #[default]
@@ -23,57 +49,570 @@ impl fmt::Display for PartOption {
         )
     }
 }
+impl PartOption {
+    pub fn parts(&self) -> &'static [Part] {
+        match self {
+            PartOption::First => &[Part::First],
+            PartOption::Second => &[Part::Second],
+            PartOption::Both => &Part::BOTH,
+        }
+    }
+}
+#[derive(Debug, Clone, Default, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OutputFormat::Text => "text",
+                OutputFormat::Json => "json",
+                OutputFormat::Csv => "csv",
+            }
+        )
+    }
+}
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Add a `dayN` crate from a template and register it with the workspace and `aoc2023`
+    New {
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Time each part over several runs, and save or compare against past timings
+    Bench(bench::BenchArgs),
+    /// Print a completion script for a shell, e.g. `runner completions bash > ~/.local/share/bash-completion/completions/runner`
+    Completions { shell: clap_complete::Shell },
+    /// Print a man page for the runner, e.g. `runner manpage > runner.1`
+    Manpage {
+        /// Write `runner.1` and a page per subcommand into this directory instead
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+    /// Explore a day interactively: load inputs, run parts and call its inspectors
+    Repl {
+        #[arg(short, long)]
+        day: u8,
+        /// Directory holding the `dayN` input files
+        #[arg(long, env = "AOC_INPUT_DIR", default_value = "./input")]
+        input_dir: PathBuf,
+    },
+    /// Answer puzzle inputs over HTTP: `GET /days`, and `POST /day/{n}/part/{p}` with the input
+    #[cfg(feature = "serve")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8023")]
+        addr: String,
+    },
+    /// Rebuild and re-run a day, and its sample tests, whenever its source or input changes
+    Watch {
+        #[arg(short, long)]
+        day: u8,
+        #[arg(short, long, default_value_t = PartOption::Both)]
+        part: PartOption,
+        /// Directory holding the `dayN` input files
+        #[arg(long, env = "AOC_INPUT_DIR", default_value = "./input")]
+        input_dir: PathBuf,
+    },
+}
+/// Runs the Advent of Code 2023 solutions against their puzzle inputs
 #[derive(Parser, Debug)]
+#[command(
+    version,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true
+)]
 struct Args {
-    #[arg(short, long)]
-    day: u8,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// A day number, or a range of days such as `3..=7`
+    #[arg(
+        short,
+        long,
+        value_parser = DaySelection::parse,
+        required_unless_present_any = ["list", "all"]
+    )]
+    day: Option<DaySelection>,
+    /// Run every registered day
+    #[arg(short, long, conflicts_with = "day")]
+    all: bool,
     #[arg(short, long, default_value_t = PartOption::Both)]
     part: PartOption,
+    /// Read the input from this file instead, or from stdin if `-`
+    #[arg(short, long, value_parser = InputSource::parse, conflicts_with = "all")]
+    input: Option<InputSource>,
+    /// Directory holding the `dayN` input files
+    #[arg(long, env = "AOC_INPUT_DIR", default_value = "./input")]
+    input_dir: PathBuf,
+    /// Run one of the day's samples from the puzzle text instead; `--check` compares against its
+    /// answers from the puzzle text
+    #[arg(short, long, value_name = "NAME", conflicts_with_all = ["input", "all", "record"])]
+    sample: Option<String>,
+    /// Report how long each part took, parsing its input included; reading the input file isn't
+    #[arg(short, long)]
+    time: bool,
+    /// Run each part this many times and report min/median/mean/max durations, which include
+    /// parsing the input
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+    /// Count each part's allocations, bytes allocated and peak live bytes
+    #[arg(long)]
+    profile_alloc: bool,
+    /// Compare answers against the answers file, failing on any mismatch
+    #[arg(short, long, conflicts_with = "record")]
+    check: bool,
+    /// Write answers into the answers file
+    #[arg(short, long)]
+    record: bool,
+    /// Where `--check` and `--record` keep known-good answers
+    #[arg(long, default_value = "./answers.toml")]
+    answers: PathBuf,
+    #[arg(short, long, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Run up to this many days, and parts of a day, at once; timings then include contention
+    #[arg(short, long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: u32,
+    /// Check each input for structural problems, reporting all of them, instead of solving it
+    #[arg(long, conflicts_with_all = ["time", "bench", "check", "record", "profile_alloc"])]
+    validate: bool,
+    /// Show how each part reads the input, line by line, for days that can explain themselves
+    #[arg(long, conflicts_with_all = ["time", "bench", "check", "record", "profile_alloc", "validate", "format"])]
+    explain: bool,
+    /// List the registered days instead of running one
+    #[arg(short, long, conflicts_with_all = ["day", "all"])]
+    list: bool,
 }
 
-const FIRST_PART: [fn(&str) -> String; 11] = [
-    day1::part1,
-    day2::part1,
-    day3::part1,
-    day4::part1,
-    day5::part1,
-    day6::part1,
-    day7::part1,
-    day8::part1,
-    day9::part1,
-    day10::part1,
-    day11::part1,
-];
-const SECOND_PART: [fn(&str) -> String; 11] = [
-    day1::part2,
-    day2::part2,
-    day3::part2,
-    day4::part2,
-    day5::part2,
-    day6::part2,
-    day7::part2,
-    day8::part2,
-    day9::part2,
-    day10::part1, /* to be swapped when part 2 is solved */
-    day11::part2,
-];
-fn main() {
-    let args = Args::parse();
-    let input_path = format!("./input/day{}", args.day);
-    let input_for_day = fs::read_to_string(&input_path)
-        .unwrap_or_else(|_| panic!("Where's the input file? didn't find it at '{}'", input_path));
-    if !matches!(&args.part, PartOption::Second) {
-        println!(
-            "day {}, part 1: {}",
-            args.day,
-            FIRST_PART[args.day as usize - 1](&input_for_day)
-        );
+fn list_days() {
+    for info in aoc2023::days() {
+        print!("day {:>2}: {}", info.day, info.name);
+        if let Some(description) = info.description {
+            print!(" ({})", description);
+        }
+        if !info.samples.is_empty() {
+            print!(" [samples: {}]", info.samples.join(", "));
+        }
+        for part in Part::BOTH {
+            if !info.parts.contains(&part) {
+                print!(" [{} not yet solved]", part);
+            }
+        }
+        println!();
+    }
+}
+
+fn report_unimplemented(selection: &DaySelection) {
+    match selection {
+        DaySelection::Single(day) => eprintln!("day {} not implemented", day),
+        DaySelection::Range(days) => eprintln!(
+            "none of days {}..={} are implemented",
+            days.start(),
+            days.end()
+        ),
+        DaySelection::All => eprintln!("no days are registered"),
     }
-    if !matches!(&args.part, PartOption::First) {
+}
+
+/// Runs each day's validation over its input, printing every problem found.
+///
+/// Returns whether all the inputs were fine; as when solving, a missing input only
+/// counts against that when it's the single day asked for.
+fn validate_days(
+    solutions: &[&dyn Solution],
+    source_for: impl Fn(&dyn Solution) -> InputSource,
+    single: bool,
+) -> bool {
+    let mut valid = true;
+    let read = for_each_input(solutions, source_for, single, |solution, source, input| {
+        let problems = solution.validate(input);
+        if problems.is_empty() {
+            println!("day {}: {} is ok", solution.day(), source);
+            return;
+        }
+        valid = false;
         println!(
-            "day {}, part 2: {}",
-            args.day,
-            SECOND_PART[args.day as usize - 1](&input_for_day)
+            "day {}: {} has {} problem{}",
+            solution.day(),
+            source,
+            problems.len(),
+            if problems.len() == 1 { "" } else { "s" }
         );
+        for problem in problems {
+            println!("{}", problem.snippet());
+        }
+    });
+    read && valid
+}
+
+/// Prints each day's account of how its parts read the input.
+///
+/// Returns whether every input could be read; lines a day couldn't make sense of are part
+/// of its explanation rather than a failure.
+fn explain_days(
+    solutions: &[&dyn Solution],
+    parts: &[Part],
+    source_for: impl Fn(&dyn Solution) -> InputSource,
+    single: bool,
+) -> bool {
+    for_each_input(solutions, source_for, single, |solution, source, input| {
+        for &part in parts {
+            match solution.explain(part, input) {
+                Some(explanation) => {
+                    println!(
+                        "day {}, {} on {}:\n{}\n",
+                        solution.day(),
+                        part,
+                        source,
+                        explanation
+                    )
+                }
+                None => println!(
+                    "day {}, {}: can't explain its answer\n",
+                    solution.day(),
+                    part
+                ),
+            }
+        }
+    })
+}
+
+/// Reads each solution's input and hands it to `f`, skipping missing inputs unless the
+/// day was asked for by itself. Returns whether every input that should be there was read.
+fn for_each_input(
+    solutions: &[&dyn Solution],
+    source_for: impl Fn(&dyn Solution) -> InputSource,
+    single: bool,
+    mut f: impl FnMut(&dyn Solution, &InputSource, &str),
+) -> bool {
+    let mut read = true;
+    for &solution in solutions {
+        let source = source_for(solution);
+        match source.read() {
+            Ok(input) => f(solution, &source, &input),
+            Err(error) if error.is_missing() && !single => {}
+            Err(error) => {
+                eprintln!("{}", error);
+                read = false;
+            }
+        }
+    }
+    read
+}
+
+/// Runs each of `parts` `repeat` times, timing every run separately, and counting the
+/// allocations of the last run when `profile_alloc` is set.
+///
+/// The input is read by the caller, so only the solution itself is measured. Every day
+/// parses inside its parts, so the timings include parsing, as `--time` and `--bench` say.
+fn run_day(
+    solution: &dyn Solution,
+    parts: &[Part],
+    source: &InputSource,
+    input: &str,
+    repeat: u32,
+    profile_alloc: bool,
+) -> Vec<PartRun> {
+    parts
+        .par_iter()
+        .map(|&part| {
+            let mut samples = Vec::with_capacity(repeat as usize);
+            let mut outcome = Outcome::Unsolved;
+            let mut allocations = None;
+            for _ in 0..repeat {
+                let start = Instant::now();
+                let answer = if profile_alloc {
+                    let (answer, stats) = allocations::measure(|| solution.solve(part, input));
+                    allocations = Some(stats);
+                    answer
+                } else {
+                    solution.solve(part, input)
+                };
+                samples.push(start.elapsed());
+                match answer {
+                    Some(Ok(answer)) => outcome = Outcome::Solved(answer),
+                    Some(Err(error)) => {
+                        // the input won't parse any better the next time round
+                        outcome = Outcome::Failed(error);
+                        break;
+                    }
+                    None => {
+                        samples.clear();
+                        allocations = None;
+                        break;
+                    }
+                }
+            }
+            PartRun {
+                day: solution.day(),
+                part,
+                input: source.to_string(),
+                outcome,
+                timings: Timings::new(samples),
+                allocations,
+            }
+        })
+        .collect()
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match &args.command {
+        Some(Command::New { day }) => {
+            return match scaffold::new_day(Path::new("."), *day) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(error) => {
+                    eprintln!("couldn't scaffold day {}: {}", day, error);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Command::Watch {
+            day,
+            part,
+            input_dir,
+        }) => {
+            let part = part.to_string();
+            return match watch::watch(Path::new("."), *day, &part, input_dir) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(error) => {
+                    eprintln!("couldn't watch day {}: {}", day, error);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Command::Bench(args)) => return bench::bench(args),
+        Some(Command::Completions { shell }) => {
+            completions::write_completions(*shell, &mut std::io::stdout());
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Manpage { out_dir }) => {
+            let written = match out_dir {
+                Some(out_dir) => completions::write_manpages(out_dir),
+                None => completions::write_manpage(&mut std::io::stdout()),
+            };
+            return match written {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(error) => {
+                    eprintln!("couldn't write the man page: {}", error);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Command::Repl { day, input_dir }) => {
+            let Some(solution) = aoc2023::find(*day) else {
+                eprintln!("day {} not implemented", day);
+                return ExitCode::FAILURE;
+            };
+            return match repl::run(solution, input_dir.clone()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(error) => {
+                    eprintln!("{}", error);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        #[cfg(feature = "serve")]
+        Some(Command::Serve { addr }) => {
+            let server = match serve::bind(addr) {
+                Ok(server) => server,
+                Err(error) => {
+                    eprintln!("couldn't listen on {}: {}", addr, error);
+                    return ExitCode::FAILURE;
+                }
+            };
+            println!("listening on http://{}", server.server_addr());
+            serve::serve(server);
+            return ExitCode::SUCCESS;
+        }
+        None => {}
+    }
+    if args.list {
+        list_days();
+        return ExitCode::SUCCESS;
+    }
+    let selection = if args.all {
+        DaySelection::All
+    } else {
+        args.day
+            .expect("clap requires --day when --list and --all are absent")
+    };
+    let solutions = selection.solutions();
+    if solutions.is_empty() {
+        report_unimplemented(&selection);
+        return ExitCode::FAILURE;
+    }
+    if args.input.is_some() && !selection.is_single() {
+        eprintln!("--input can only be used when running a single day");
+        return ExitCode::FAILURE;
+    }
+    if args.sample.is_some() && !selection.is_single() {
+        eprintln!("--sample can only be used when running a single day");
+        return ExitCode::FAILURE;
+    }
+    let source_for = |solution: &dyn Solution| match (&args.sample, &args.input) {
+        (Some(name), _) => InputSource::Sample {
+            day: solution.day(),
+            name: name.clone(),
+        },
+        (None, Some(input)) => input.clone(),
+        (None, None) => InputSource::for_day(&args.input_dir, solution.day()),
+    };
+    if args.validate {
+        return if validate_days(&solutions, source_for, selection.is_single()) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+    if args.explain {
+        let parts = args.part.parts();
+        return if explain_days(&solutions, parts, source_for, selection.is_single()) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+    // samples carry their own expected answers
+    let mut answers = if (args.check && args.sample.is_none()) || args.record {
+        match Answers::load(&args.answers) {
+            Ok(answers) => Some(answers),
+            Err(error) => {
+                eprintln!("{}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+    let repeat = args.bench.unwrap_or(1);
+    if args.profile_alloc {
+        allocations::enable();
+    }
+    let parts = args.part.parts();
+
+    let pool = match rayon::ThreadPoolBuilder::new()
+        .num_threads(args.jobs as usize)
+        .build()
+    {
+        Ok(pool) => pool,
+        Err(error) => {
+            eprintln!("couldn't start {} threads: {}", args.jobs, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    // collecting a parallel iterator keeps the days in order, however they finish
+    let results = pool.install(|| {
+        solutions
+            .par_iter()
+            .map(|&solution| {
+                let source = source_for(solution);
+                match source.read() {
+                    Ok(input_for_day) => Ok(run_day(
+                        solution,
+                        parts,
+                        &source,
+                        &input_for_day,
+                        repeat,
+                        args.profile_alloc,
+                    )),
+                    Err(error) => Err(InputFailure {
+                        day: solution.day(),
+                        skipped: error.is_missing() && !selection.is_single(),
+                        error,
+                    }),
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut runs = Vec::new();
+    let mut input_failures = Vec::new();
+    for result in results {
+        match result {
+            Ok(runs_for_day) => runs.extend(runs_for_day),
+            Err(failure) => input_failures.push(failure),
+        }
+    }
+    // a missing input only counts as a failure when it was explicitly asked for
+    let mut failed = input_failures.iter().any(|failure| !failure.skipped)
+        || runs
+            .iter()
+            .any(|run| matches!(run.outcome, Outcome::Failed(_)));
+
+    match args.format {
+        OutputFormat::Text if selection.is_single() => {
+            if let Some(failure) = input_failures.first() {
+                eprintln!("{}", failure.error);
+                return ExitCode::FAILURE;
+            }
+            report::print_lines(&runs, args.time || args.bench.is_some());
+        }
+        OutputFormat::Text => {
+            report::print_table(&runs);
+            report::print_errors(&runs);
+            let (missing, unreadable): (Vec<_>, Vec<_>) =
+                input_failures.iter().partition(|failure| failure.skipped);
+            for failure in unreadable {
+                eprintln!("{}", failure.error);
+            }
+            if !missing.is_empty() {
+                println!(
+                    "\nskipped (no input file): {}",
+                    missing
+                        .iter()
+                        .map(|failure| format!("day {}", failure.day))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+        OutputFormat::Json => report::print_json(&runs, &input_failures, parts),
+        OutputFormat::Csv => report::print_csv(&runs, &input_failures, parts),
+    }
+
+    if args.check {
+        // keep stdout parseable when it's carrying structured output
+        let structured = !matches!(args.format, OutputFormat::Text);
+        if !structured {
+            println!();
+        }
+        for run in runs.iter() {
+            let expected = match &args.sample {
+                Some(name) => aoc2023::find(run.day)
+                    .and_then(|solution| solution.samples().expected(name, run.part)),
+                None => answers
+                    .as_ref()
+                    .expect("loaded above for --check")
+                    .expected(run.day, run.part),
+            };
+            let verdict = Verdict::of(expected, &run.outcome);
+            if structured {
+                eprintln!("day {}, {}: {}", run.day, run.part, verdict);
+            } else {
+                println!("day {}, {}: {}", run.day, run.part, verdict);
+            }
+            failed |= verdict.is_failure();
+        }
+    }
+    if let Some(answers) = answers.as_mut().filter(|_| args.record) {
+        for run in runs.iter() {
+            if let Outcome::Solved(answer) = &run.outcome {
+                answers.record(run.day, run.part, answer);
+            }
+        }
+        if let Err(error) = answers.save(&args.answers) {
+            eprintln!("couldn't write '{}': {}", args.answers.display(), error);
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
     }
 }
@@ -0,0 +1,184 @@
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use aoc2023::{Part, Solution};
+
+use crate::input::InputSource;
+
+/// An interactive session on one day at a time, for running its parts and inspectors
+/// against an input without rebuilding anything.
+pub struct Repl {
+    solution: &'static dyn Solution,
+    input_dir: PathBuf,
+    input: Option<(InputSource, String)>,
+}
+impl Repl {
+    pub fn new(solution: &'static dyn Solution, input_dir: PathBuf) -> Repl {
+        Repl {
+            solution,
+            input_dir,
+            input: None,
+        }
+    }
+
+    /// Runs one line of input, returning what to show for it, or `None` to quit.
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        Some(match words.as_slice() {
+            [] => String::new(),
+            ["quit" | "exit"] => return None,
+            ["help"] => self.help(),
+            ["day", day] => match day.parse().ok().and_then(aoc2023::find) {
+                Some(solution) => {
+                    self.solution = solution;
+                    self.input = None;
+                    format!("day {}: {}", solution.day(), solution.name())
+                }
+                None => format!("day {} isn't registered", day),
+            },
+            ["load"] => self.load(InputSource::for_day(&self.input_dir, self.solution.day())),
+            ["load", "sample", name] => self.load(InputSource::Sample {
+                day: self.solution.day(),
+                name: name.to_string(),
+            }),
+            ["load", path] => self.load(InputSource::File(PathBuf::from(path))),
+            ["part1"] => self.run_part(Part::First),
+            ["part2"] => self.run_part(Part::Second),
+            _ => self.inspect(&words),
+        })
+    }
+
+    fn help(&self) -> String {
+        let mut commands = vec![
+            ("load".to_string(), "read this day's input file"),
+            ("load <path>".to_string(), "read the input from a file"),
+            (
+                "load sample <name>".to_string(),
+                "use one of the puzzle's samples",
+            ),
+            ("part1, part2".to_string(), "run a part on the loaded input"),
+            ("day <n>".to_string(), "switch to another day"),
+            ("quit".to_string(), "leave"),
+        ];
+        for inspector in self.solution.inspectors() {
+            let usage = format!("{} {}", inspector.command, inspector.args);
+            commands.push((usage.trim_end().to_string(), inspector.help));
+        }
+        let width = commands
+            .iter()
+            .map(|(usage, _)| usage.len())
+            .max()
+            .unwrap_or(0);
+        commands
+            .iter()
+            .map(|(usage, help)| format!("{:<width$}  {}", usage, help))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn load(&mut self, source: InputSource) -> String {
+        match source.read() {
+            Ok(input) => {
+                let message = format!("loaded {} ({} lines)", source, input.lines().count());
+                self.input = Some((source, input));
+                message
+            }
+            Err(error) => error.to_string(),
+        }
+    }
+
+    fn loaded_input(&self) -> Result<&str, String> {
+        self.input
+            .as_ref()
+            .map(|(_, input)| input.as_str())
+            .ok_or_else(|| "nothing loaded yet; try 'load' or 'load sample <name>'".to_string())
+    }
+
+    fn run_part(&self, part: Part) -> String {
+        let input = match self.loaded_input() {
+            Ok(input) => input,
+            Err(message) => return message,
+        };
+        let start = Instant::now();
+        let answer = self.solution.solve(part, input);
+        let elapsed = start.elapsed();
+        match answer {
+            Some(Ok(answer)) => format!("{}: {} ({:.2?})", part, answer, elapsed),
+            Some(Err(error)) => format!("{}: error\n{}", part, error.snippet()),
+            None => format!("{}: not yet solved", part),
+        }
+    }
+
+    fn inspect(&self, words: &[&str]) -> String {
+        // prefer the most specific command, should one be a prefix of another
+        let found = self
+            .solution
+            .inspectors()
+            .iter()
+            .filter_map(|inspector| Some((inspector, inspector.matches(words)?)))
+            .max_by_key(|(inspector, _)| inspector.command.len());
+        let Some((inspector, args)) = found else {
+            return format!("unknown command '{}'; try 'help'", words.join(" "));
+        };
+        let input = match self.loaded_input() {
+            Ok(input) => input,
+            Err(message) => return message,
+        };
+        match (inspector.run)(input, args) {
+            Ok(found) => found,
+            Err(error) => match error.downcast_ref::<aoc2023::Error>() {
+                Some(error) => format!("error\n{}", error.snippet()),
+                None => format!("error: {}", error),
+            },
+        }
+    }
+}
+
+/// Reads commands from stdin until it closes or the user quits.
+pub fn run(solution: &'static dyn Solution, input_dir: PathBuf) -> io::Result<()> {
+    let mut repl = Repl::new(solution, input_dir);
+    println!(
+        "day {}: {} (type 'help' for commands)",
+        solution.day(),
+        solution.name()
+    );
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("day{}> ", repl.solution.day());
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        match repl.execute(&line) {
+            Some(output) if output.is_empty() => {}
+            Some(output) => println!("{}", output),
+            None => return Ok(()),
+        }
+    }
+}
+
+#[test]
+fn sample_can_be_loaded_run_and_inspected() {
+    let mut repl = Repl::new(aoc2023::find(5).unwrap(), PathBuf::from("./input"));
+    assert!(repl.execute("part1").unwrap().starts_with("nothing loaded"));
+    assert_eq!(
+        repl.execute("load sample example").unwrap(),
+        "loaded sample example (33 lines)"
+    );
+    assert!(repl.execute("part1").unwrap().starts_with("part 1: 35 ("));
+    assert!(repl
+        .execute("map seed 79")
+        .unwrap()
+        .ends_with("-> location 82"));
+    assert_eq!(
+        repl.execute("map seed").unwrap(),
+        "error: expected a seed number"
+    );
+    assert!(repl
+        .execute("frobnicate")
+        .unwrap()
+        .starts_with("unknown command"));
+    assert_eq!(repl.execute("quit"), None);
+}
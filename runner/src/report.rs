@@ -0,0 +1,323 @@
+use aoc2023::{Answer, Error, Part};
+use serde_json::json;
+
+use crate::allocations::{AllocStats, Bytes};
+use crate::input::InputError;
+use crate::timing::Timings;
+
+pub enum Outcome {
+    Solved(Answer),
+    Failed(Error),
+    Unsolved,
+}
+impl Outcome {
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved(_) => "ok",
+            Outcome::Failed(_) => "error",
+            Outcome::Unsolved => "unsolved",
+        }
+    }
+    fn answer(&self) -> Option<&Answer> {
+        match self {
+            Outcome::Solved(answer) => Some(answer),
+            Outcome::Failed(_) | Outcome::Unsolved => None,
+        }
+    }
+    fn error(&self) -> Option<&Error> {
+        match self {
+            Outcome::Failed(error) => Some(error),
+            Outcome::Solved(_) | Outcome::Unsolved => None,
+        }
+    }
+}
+pub struct PartRun {
+    pub day: u8,
+    pub part: Part,
+    /// Where the input came from, for reporting
+    pub input: String,
+    pub outcome: Outcome,
+    pub timings: Timings,
+    /// What the part allocated, when profiling allocations
+    pub allocations: Option<AllocStats>,
+}
+
+/// Prints a line per part run, in the same shape as the answers on the puzzle pages.
+pub fn print_lines(runs: &[PartRun], with_timings: bool) {
+    for run in runs {
+        match &run.outcome {
+            Outcome::Solved(answer) => {
+                let details = [
+                    with_timings.then(|| run.timings.to_string()),
+                    run.allocations.map(|stats| stats.to_string()),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+                if details.is_empty() {
+                    println!("day {}, {}: {}", run.day, run.part, answer);
+                } else {
+                    println!(
+                        "day {}, {}: {} ({})",
+                        run.day,
+                        run.part,
+                        answer,
+                        details.join("; ")
+                    );
+                }
+            }
+            Outcome::Failed(_) => println!("day {}, {}: error", run.day, run.part),
+            Outcome::Unsolved => println!("day {}, {}: not yet solved", run.day, run.part),
+        }
+    }
+    print_errors(runs);
+}
+
+/// Prints every error a solution returned to stderr, with the offending input line.
+pub fn print_errors(runs: &[PartRun]) {
+    for run in runs {
+        if let Outcome::Failed(error) = &run.outcome {
+            eprintln!(
+                "\nday {}, {}: error on line {}, column {}\n{}",
+                run.day,
+                run.part,
+                error.line,
+                error.column,
+                error.snippet()
+            );
+        }
+    }
+}
+
+/// Prints one row per part run, with columns padded to line up.
+///
+/// Benchmarked runs get a column per statistic instead of a single time, and profiled
+/// allocations get columns of their own.
+pub fn print_table(runs: &[PartRun]) {
+    let benchmarked = runs.iter().any(|run| run.timings.runs() > 1);
+    let profiled = runs.iter().any(|run| run.allocations.is_some());
+    let mut header = vec!["day", "part", "answer"];
+    if benchmarked {
+        header.extend(["min", "median", "mean", "max"]);
+    } else {
+        header.push("time");
+    }
+    if profiled {
+        header.extend(["allocs", "allocated", "peak"]);
+    }
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let mut row = vec![run.day.to_string(), run.part.number().to_string()];
+            match &run.outcome {
+                Outcome::Solved(answer) => {
+                    row.push(answer.to_string());
+                    let t = &run.timings;
+                    if benchmarked {
+                        row.extend(
+                            [t.min(), t.median(), t.mean(), t.max()].map(|d| format!("{:.2?}", d)),
+                        );
+                    } else {
+                        row.push(format!("{:.2?}", t.min()));
+                    }
+                    if let Some(stats) = run.allocations {
+                        row.extend([
+                            stats.allocations.to_string(),
+                            Bytes(stats.bytes).to_string(),
+                            Bytes(stats.peak).to_string(),
+                        ]);
+                    }
+                }
+                Outcome::Failed(_) => {
+                    row.push("error".to_string());
+                    row.resize(header.len(), "-".to_string());
+                }
+                Outcome::Unsolved => {
+                    row.push("not yet solved".to_string());
+                    row.resize(header.len(), "-".to_string());
+                }
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let print_row = |cells: &[&str]| {
+        let line = cells
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(column, (cell, &width))| {
+                // numbers read better right-aligned, apart from the answer which might be text
+                if column == 2 {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line);
+    };
+    print_row(&header);
+    for row in rows.iter() {
+        print_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+}
+
+/// A day that never got to run because its input couldn't be read.
+pub struct InputFailure {
+    pub day: u8,
+    pub error: InputError,
+    /// Whether the input is just missing from a run over several days, which skips the day
+    /// rather than failing it
+    pub skipped: bool,
+}
+
+/// One flat record per part, whether it ran or not; shared by the structured formats.
+struct Record<'a> {
+    day: u8,
+    part: Part,
+    status: &'static str,
+    answer: Option<&'a Answer>,
+    duration_ns: Option<u128>,
+    allocations: Option<AllocStats>,
+    input: String,
+    error: Option<String>,
+}
+fn records<'a>(
+    runs: &'a [PartRun],
+    input_failures: &'a [InputFailure],
+    parts: &'a [Part],
+) -> Vec<Record<'a>> {
+    let mut records = runs
+        .iter()
+        .map(|run| Record {
+            day: run.day,
+            part: run.part,
+            status: run.outcome.status(),
+            answer: run.outcome.answer(),
+            duration_ns: (run.timings.runs() > 0).then(|| run.timings.median().as_nanos()),
+            allocations: run.allocations,
+            input: run.input.clone(),
+            error: run.outcome.error().map(|error| error.to_string()),
+        })
+        .chain(input_failures.iter().flat_map(|failure| {
+            parts.iter().map(|&part| Record {
+                day: failure.day,
+                part,
+                status: if failure.skipped { "skipped" } else { "error" },
+                answer: None,
+                duration_ns: None,
+                allocations: None,
+                input: failure.error.source().to_string(),
+                error: Some(failure.error.to_string()),
+            })
+        }))
+        .collect::<Vec<_>>();
+    records.sort_by_key(|record| (record.day, record.part));
+    records
+}
+#[test]
+fn skipped_days_are_not_errors() {
+    let failure = |day, skipped| InputFailure {
+        day,
+        error: crate::input::InputSource::for_day(std::path::Path::new("./does-not-exist"), day)
+            .read()
+            .unwrap_err(),
+        skipped,
+    };
+    let failures = [failure(1, true), failure(2, false)];
+    let statuses = records(&[], &failures, &[Part::First])
+        .iter()
+        .map(|record| (record.day, record.status))
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, vec![(1, "skipped"), (2, "error")]);
+}
+
+/// Integer answers become JSON numbers, so consumers don't have to parse them.
+pub fn answer_json(answer: &Answer) -> serde_json::Value {
+    match answer.as_integer() {
+        Some(n) => json!(n),
+        None => json!(answer.to_string()),
+    }
+}
+
+/// Prints every part as a JSON array, for consumption by other tools.
+///
+/// Benchmarked parts report their median duration. Allocation counts are null unless
+/// they were profiled.
+pub fn print_json(runs: &[PartRun], input_failures: &[InputFailure], parts: &[Part]) {
+    let records = records(runs, input_failures, parts)
+        .into_iter()
+        .map(|record| {
+            json!({
+                "day": record.day,
+                "part": record.part.number(),
+                "status": record.status,
+                "answer": record.answer.map(answer_json),
+                "duration_ns": record.duration_ns,
+                "allocations": record.allocations.map(|stats| stats.allocations),
+                "bytes_allocated": record.allocations.map(|stats| stats.bytes),
+                "peak_bytes": record.allocations.map(|stats| stats.peak),
+                "input": record.input,
+                "error": record.error,
+            })
+        })
+        .collect::<Vec<_>>();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&records).expect("JSON values always serialize")
+    );
+}
+
+/// Prints every part as CSV with a header row; absent values are left empty.
+pub fn print_csv(runs: &[PartRun], input_failures: &[InputFailure], parts: &[Part]) {
+    println!(
+        "day,part,status,answer,duration_ns,allocations,bytes_allocated,peak_bytes,input,error"
+    );
+    for record in records(runs, input_failures, parts) {
+        println!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            record.day,
+            record.part.number(),
+            record.status,
+            csv_field(
+                &record
+                    .answer
+                    .map(|answer| answer.to_string())
+                    .unwrap_or_default()
+            ),
+            record
+                .duration_ns
+                .map(|ns| ns.to_string())
+                .unwrap_or_default(),
+            optional(record.allocations.map(|stats| stats.allocations)),
+            optional(record.allocations.map(|stats| stats.bytes)),
+            optional(record.allocations.map(|stats| stats.peak)),
+            csv_field(&record.input),
+            csv_field(record.error.as_deref().unwrap_or_default()),
+        );
+    }
+}
+fn optional(value: Option<u64>) -> String {
+    value.map(|n| n.to_string()).unwrap_or_default()
+}
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[test]
+fn csv_fields_are_quoted_only_when_needed() {
+    assert_eq!(csv_field("4361"), "4361");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+}
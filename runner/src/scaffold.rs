@@ -0,0 +1,286 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Sets up `dayN` in the workspace at `root`: the crate and its samples, its workspace membership,
+/// the `aoc2023` crate's dependency on it, its registry entry and an empty input file.
+///
+/// Each step is skipped if it's already been done, so nothing written by hand is lost.
+pub fn new_day(root: &Path, day: u8) -> io::Result<()> {
+    let name = format!("day{}", day);
+    let workspace_manifest = root.join("Cargo.toml");
+    if !fs::read_to_string(&workspace_manifest)?.contains("[workspace]") {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "'{}' isn't the workspace manifest; run this from the workspace root",
+                workspace_manifest.display()
+            ),
+        ));
+    }
+
+    let crate_dir = root.join(&name);
+    create_file(&crate_dir.join("Cargo.toml"), &crate_manifest(day))?;
+    create_file(&crate_dir.join("src").join("lib.rs"), &crate_source(day))?;
+    create_file(&crate_dir.join("samples").join("example.txt"), "")?;
+    create_file(
+        &crate_dir.join("samples").join("answers.toml"),
+        "[example]\n# part1 = \n# part2 = \n",
+    )?;
+    edit_file(&workspace_manifest, |text| {
+        insert_in_day_order(
+            text,
+            "members = [",
+            "\"day",
+            day,
+            &format!("    \"{}\"", name),
+            true,
+        )
+    })?;
+    edit_file(&root.join("aoc2023").join("Cargo.toml"), |text| {
+        insert_in_day_order(
+            text,
+            "[dependencies]",
+            "day",
+            day,
+            &format!("{} = {{ path = \"../{}\" }}", name, name),
+            false,
+        )
+    })?;
+    edit_file(&root.join("aoc2023").join("src").join("lib.rs"), |text| {
+        insert_in_day_order(
+            text,
+            "const SOLUTIONS",
+            "&day",
+            day,
+            &format!("    &{}::Day{},", name, day),
+            false,
+        )
+    })?;
+    create_file(&root.join("input").join(&name), "")
+}
+
+/// Writes `contents` to a new file, leaving any existing one as it is.
+fn create_file(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+    {
+        Ok(mut file) => {
+            file.write_all(contents.as_bytes())?;
+            println!("created {}", path.display());
+            Ok(())
+        }
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+            println!("kept existing {}", path.display());
+            Ok(())
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Rewrites a file with `edit`, which returns `None` when there's nothing to change.
+fn edit_file(
+    path: &Path,
+    edit: impl FnOnce(&str) -> Result<Option<String>, String>,
+) -> io::Result<()> {
+    let text = fs::read_to_string(path)?;
+    let edited = edit(&text).map_err(|problem| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("couldn't update {}: {}", path.display(), problem),
+        )
+    })?;
+    match edited {
+        Some(edited) => {
+            fs::write(path, edited)?;
+            println!("updated {}", path.display());
+        }
+        None => println!("{} already lists it", path.display()),
+    }
+    Ok(())
+}
+
+/// The day number of a line like `{prefix}7...`, ignoring indentation.
+fn day_of(line: &str, prefix: &str) -> Option<u8> {
+    let rest = line.trim_start().strip_prefix(prefix)?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(rest, |end| &rest[..end]);
+    digits.parse().ok()
+}
+
+/// Adds `entry` among the lines starting with `prefix` followed by a day number,
+/// keeping them in day order; `None` if `day` is already there.
+///
+/// With no days listed yet, the entry goes straight after the line starting with `opening`,
+/// which begins the list. For a comma-separated list whose last entry has no trailing comma,
+/// that style is kept.
+fn insert_in_day_order(
+    text: &str,
+    opening: &str,
+    prefix: &str,
+    day: u8,
+    entry: &str,
+    comma_separated: bool,
+) -> Result<Option<String>, String> {
+    let mut lines = text.lines().map(str::to_string).collect::<Vec<_>>();
+    let days = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| day_of(line, prefix).map(|d| (index, d)))
+        .collect::<Vec<_>>();
+    if days.iter().any(|&(_, d)| d == day) {
+        return Ok(None);
+    }
+    let comma = if comma_separated { "," } else { "" };
+    match (days.iter().find(|&&(_, d)| d > day), days.last()) {
+        (Some(&(index, _)), _) => {
+            lines.insert(index, format!("{}{}", entry, comma));
+        }
+        (None, None) => {
+            let index = lines
+                .iter()
+                .position(|line| line.trim_start().starts_with(opening))
+                .ok_or_else(|| format!("there's no '{}' line to list the day after", opening))?;
+            lines.insert(index + 1, format!("{}{}", entry, comma));
+        }
+        (None, Some(&(last, _))) => {
+            if comma_separated && !lines[last].trim_end().ends_with(',') {
+                lines[last].push(',');
+                lines.insert(last + 1, entry.to_string());
+            } else {
+                lines.insert(last + 1, format!("{}{}", entry, comma));
+            }
+        }
+    }
+    let mut edited = lines.join("\n");
+    if text.ends_with('\n') {
+        edited.push('\n');
+    }
+    Ok(Some(edited))
+}
+
+#[test]
+fn new_days_keep_lists_in_order() {
+    let members = "members = [\n    \"runner\",\n    \"day1\",\n    \"day3\"\n]\n";
+    let insert = |text, prefix, day, entry, comma_separated| {
+        insert_in_day_order(text, "members = [", prefix, day, entry, comma_separated)
+    };
+    assert_eq!(
+        insert(members, "\"day", 4, "    \"day4\"", true),
+        Ok(Some(
+            "members = [\n    \"runner\",\n    \"day1\",\n    \"day3\",\n    \"day4\"\n]\n"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        insert(members, "\"day", 2, "    \"day2\"", true),
+        Ok(Some(
+            "members = [\n    \"runner\",\n    \"day1\",\n    \"day2\",\n    \"day3\"\n]\n"
+                .to_string()
+        ))
+    );
+    assert_eq!(insert(members, "\"day", 3, "", true), Ok(None));
+
+    let registry = "    &day9::Day9,\n    &day10::Day10,\n];\n";
+    assert_eq!(
+        insert(registry, "&day", 11, "    &day11::Day11,", false),
+        Ok(Some(
+            "    &day9::Day9,\n    &day10::Day10,\n    &day11::Day11,\n];\n".to_string()
+        ))
+    );
+}
+#[test]
+fn first_day_goes_after_the_opening() {
+    let members = "members = [\n    \"runner\"\n]\n";
+    assert_eq!(
+        insert_in_day_order(members, "members = [", "\"day", 1, "    \"day1\"", true),
+        Ok(Some(
+            "members = [\n    \"day1\",\n    \"runner\"\n]\n".to_string()
+        ))
+    );
+    let registry = "const SOLUTIONS: &[&dyn Solution] = &[\n];\n";
+    assert_eq!(
+        insert_in_day_order(
+            registry,
+            "const SOLUTIONS",
+            "&day",
+            1,
+            "    &day1::Day1,",
+            false
+        ),
+        Ok(Some(
+            "const SOLUTIONS: &[&dyn Solution] = &[\n    &day1::Day1,\n];\n".to_string()
+        ))
+    );
+    assert!(insert_in_day_order("", "const SOLUTIONS", "&day", 1, "", false).is_err());
+}
+
+fn crate_manifest(day: u8) -> String {
+    format!(
+        r#"[package]
+name = "day{day}"
+version = "0.1.0"
+edition = "2021"
+
+# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+[dependencies]
+common = {{ path = "../common" }}
+"#
+    )
+}
+
+fn crate_source(day: u8) -> String {
+    format!(
+        r#"use common::{{samples, Answer, Error, Part, Samples, Solution}};
+
+const DAY: u8 = {day};
+const SAMPLES: Samples = samples!("example");
+
+#[test]
+#[ignore = "solve part 1 and fill in samples/example.txt and samples/answers.toml first"]
+fn part1_on_sample() {{
+    SAMPLES.check("example", Part::First, |input| {{
+        Day{day}.part1(input).expect("part 1 is solved")
+    }});
+}}
+
+#[test]
+#[ignore = "solve part 2 and fill in samples/example.txt and samples/answers.toml first"]
+fn part2_on_sample() {{
+    SAMPLES.check("example", Part::Second, |input| {{
+        Day{day}.part2(input).expect("part 2 is solved")
+    }});
+}}
+
+pub struct Day{day};
+impl Solution for Day{day} {{
+    fn day(&self) -> u8 {{
+        DAY
+    }}
+    fn name(&self) -> &'static str {{
+        "Day {day}"
+    }}
+    fn samples(&self) -> &'static Samples {{
+        &SAMPLES
+    }}
+    // drop this once both parts are solved
+    fn is_solved(&self, _part: Part) -> bool {{
+        false
+    }}
+    fn part1(&self, _input: &str) -> Option<Result<Answer, Error>> {{
+        None
+    }}
+    fn part2(&self, _input: &str) -> Option<Result<Answer, Error>> {{
+        None
+    }}
+}}
+"#
+    )
+}
@@ -0,0 +1,66 @@
+use std::ops::RangeInclusive;
+
+use aoc2023::Solution;
+
+/// Which days a single invocation should run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaySelection {
+    Single(u8),
+    Range(RangeInclusive<u8>),
+    All,
+}
+impl DaySelection {
+    /// Accepts a day number or a range of them, written like Rust ranges (`3..=7`, `3..8`).
+    pub fn parse(s: &str) -> Result<DaySelection, String> {
+        fn parse_day(s: &str) -> Result<u8, String> {
+            s.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("'{}' is not a day number", s))
+        }
+        if let Some((start, end)) = s.split_once("..=") {
+            Ok(DaySelection::Range(parse_day(start)?..=parse_day(end)?))
+        } else if let Some((start, end)) = s.split_once("..") {
+            let end = parse_day(end)?
+                .checked_sub(1)
+                .ok_or_else(|| format!("'{}' is an empty range", s))?;
+            Ok(DaySelection::Range(parse_day(start)?..=end))
+        } else {
+            parse_day(s).map(DaySelection::Single)
+        }
+    }
+
+    pub fn is_single(&self) -> bool {
+        matches!(self, DaySelection::Single(_))
+    }
+
+    /// The registered solutions this selection covers, in day order.
+    pub fn solutions(&self) -> Vec<&'static dyn Solution> {
+        match self {
+            DaySelection::Single(day) => aoc2023::find(*day).into_iter().collect(),
+            DaySelection::Range(days) => aoc2023::all()
+                .filter(|solution| days.contains(&solution.day()))
+                .collect(),
+            DaySelection::All => aoc2023::all().collect(),
+        }
+    }
+}
+
+#[test]
+fn test_parse_day_selection() {
+    assert_eq!(DaySelection::parse("7"), Ok(DaySelection::Single(7)));
+    assert_eq!(DaySelection::parse("3..=7"), Ok(DaySelection::Range(3..=7)));
+    assert_eq!(DaySelection::parse("3..8"), Ok(DaySelection::Range(3..=7)));
+    assert!(DaySelection::parse("3..0").is_err());
+    assert!(DaySelection::parse("three").is_err());
+}
+#[test]
+fn range_only_selects_registered_days() {
+    // days that can't exist, so this holds however many get added
+    let days = DaySelection::Range(0..=1)
+        .solutions()
+        .iter()
+        .map(|s| s.day())
+        .collect::<Vec<_>>();
+    assert_eq!(days, vec![1]);
+    assert!(DaySelection::Range(26..=30).solutions().is_empty());
+}
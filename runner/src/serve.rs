@@ -0,0 +1,150 @@
+use std::io;
+use std::time::Instant;
+
+use aoc2023::{Part, SolveError};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::report::answer_json;
+
+/// Binds the server without serving anything yet, so callers can learn the port it got.
+pub fn bind(addr: &str) -> io::Result<Server> {
+    Server::http(addr).map_err(io::Error::other)
+}
+
+/// Answers requests one at a time until the process is stopped:
+///
+/// - `GET /days` lists the registered days
+/// - `POST /day/{n}/part/{p}` solves a part, with the puzzle input as the body
+pub fn serve(server: Server) {
+    for request in server.incoming_requests() {
+        handle(request);
+    }
+}
+
+fn handle(mut request: Request) {
+    let mut body = String::new();
+    let (status, reply) = match request.as_reader().read_to_string(&mut body) {
+        Ok(_) => respond(request.method(), request.url(), &body),
+        Err(_) => (400, json!({ "error": "the body isn't UTF-8 text" })),
+    };
+    let response = Response::from_string(reply.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("valid header"));
+    if let Err(error) = request.respond(response) {
+        eprintln!("couldn't send a response: {}", error);
+    }
+}
+
+/// Routes a request to its status code and JSON reply.
+fn respond(method: &Method, url: &str, body: &str) -> (u16, Value) {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["days"]) => (200, days()),
+        (Method::Post, ["day", day, "part", part]) => solve(day, part, body),
+        (_, ["days"] | ["day", _, "part", _]) => (405, json!({ "error": "method not allowed" })),
+        _ => (
+            404,
+            json!({ "error": format!("no endpoint at '{}'", path) }),
+        ),
+    }
+}
+
+fn days() -> Value {
+    aoc2023::days()
+        .map(|info| {
+            json!({
+                "day": info.day,
+                "name": info.name,
+                "description": info.description,
+                "parts": info.parts.iter().map(Part::number).collect::<Vec<_>>(),
+                "samples": info.samples,
+            })
+        })
+        .collect()
+}
+
+fn solve(day: &str, part: &str, input: &str) -> (u16, Value) {
+    let Ok(day) = day.parse::<u8>() else {
+        return (
+            404,
+            json!({ "error": format!("'{}' is not a day number", day) }),
+        );
+    };
+    let part = match part {
+        "1" => Part::First,
+        "2" => Part::Second,
+        _ => return (404, json!({ "error": "the part is either 1 or 2" })),
+    };
+    let start = Instant::now();
+    let solved = aoc2023::solve(day, part, input);
+    let duration_ns = start.elapsed().as_nanos();
+    match solved {
+        Ok(answer) => (
+            200,
+            json!({
+                "day": day,
+                "part": part.number(),
+                "status": "ok",
+                "answer": answer_json(&answer),
+                "duration_ns": duration_ns,
+            }),
+        ),
+        Err(SolveError::Input(error)) => (
+            422,
+            json!({
+                "day": day,
+                "part": part.number(),
+                "status": "error",
+                "error": error.to_string(),
+                "line": error.line,
+                "column": error.column,
+                "expected": error.expected,
+                "duration_ns": duration_ns,
+            }),
+        ),
+        Err(error @ SolveError::UnknownDay(_)) => (404, json!({ "error": error.to_string() })),
+        Err(error @ SolveError::Unsolved { .. }) => (501, json!({ "error": error.to_string() })),
+    }
+}
+
+#[test]
+fn routes_map_to_status_codes() {
+    assert_eq!(respond(&Method::Get, "/days", "").0, 200);
+    assert_eq!(respond(&Method::Post, "/day/26/part/1", "").0, 404);
+    assert_eq!(respond(&Method::Post, "/day/10/part/2", "").0, 501);
+    assert_eq!(respond(&Method::Post, "/day/6/part/3", "").0, 404);
+    assert_eq!(respond(&Method::Get, "/day/6/part/1", "").0, 405);
+    assert_eq!(respond(&Method::Get, "/nowhere", "").0, 404);
+    let (status, reply) = respond(&Method::Post, "/day/6/part/1", "Time: 7\n");
+    assert_eq!((status, reply["line"].as_u64()), (422, Some(2)));
+}
+#[test]
+fn answers_over_localhost() {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    let server = bind("127.0.0.1:0").unwrap();
+    let addr = server.server_addr().to_ip().unwrap();
+    std::thread::spawn(move || handle(server.recv().unwrap()));
+
+    let input = "Time:      7  15   30\nDistance:  9  40  200\n";
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST /day/6/part/1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        input.len(),
+        input
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    let reply = serde_json::from_str::<Value>(body).unwrap();
+    assert_eq!(reply["answer"], json!(288));
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Wall-clock durations of one or more runs of the same part.
+#[derive(Debug, Clone, Default)]
+pub struct Timings(Vec<Duration>);
+impl Timings {
+    pub fn new(mut samples: Vec<Duration>) -> Timings {
+        samples.sort();
+        Timings(samples)
+    }
+    pub fn runs(&self) -> usize {
+        self.0.len()
+    }
+    pub fn min(&self) -> Duration {
+        self.0.first().copied().unwrap_or_default()
+    }
+    pub fn max(&self) -> Duration {
+        self.0.last().copied().unwrap_or_default()
+    }
+    pub fn median(&self) -> Duration {
+        match self.0.len() {
+            0 => Duration::ZERO,
+            len if len % 2 == 1 => self.0[len / 2],
+            len => (self.0[len / 2 - 1] + self.0[len / 2]) / 2,
+        }
+    }
+    pub fn mean(&self) -> Duration {
+        match self.0.len() {
+            0 => Duration::ZERO,
+            len => self.0.iter().sum::<Duration>() / len as u32,
+        }
+    }
+}
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.runs() == 1 {
+            write!(f, "{:.2?}", self.min())
+        } else {
+            write!(
+                f,
+                "{} runs: min {:.2?}, median {:.2?}, mean {:.2?}, max {:.2?}",
+                self.runs(),
+                self.min(),
+                self.median(),
+                self.mean(),
+                self.max()
+            )
+        }
+    }
+}
+
+#[test]
+fn test_timings_statistics() {
+    let timings = Timings::new(
+        [4, 1, 3, 2]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect(),
+    );
+    assert_eq!(timings.min(), Duration::from_millis(1));
+    assert_eq!(timings.max(), Duration::from_millis(4));
+    assert_eq!(timings.median(), Duration::from_micros(2500));
+    assert_eq!(timings.mean(), Duration::from_micros(2500));
+}
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde_json::Value;
+
+/// How long to wait for an editor to finish a burst of writes before rebuilding.
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// Rebuilds and re-runs `day` whenever its source or input changes, until interrupted.
+///
+/// Each run goes through cargo, so the answers always come from the code as it is now
+/// rather than from the runner that's doing the watching.
+pub fn watch(root: &Path, day: u8, part: &str, input_dir: &Path) -> io::Result<()> {
+    let src = root
+        .join(format!("day{}", day))
+        .join("src")
+        .canonicalize()?;
+    fs::create_dir_all(input_dir)?;
+    let watched_input_dir = input_dir.canonicalize()?;
+    let input_file = watched_input_dir.join(format!("day{}", day));
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+    watcher
+        .watch(&src, RecursiveMode::Recursive)
+        .map_err(io::Error::other)?;
+    // the input file may not exist yet, so watch the directory it'll appear in
+    watcher
+        .watch(&watched_input_dir, RecursiveMode::NonRecursive)
+        .map_err(io::Error::other)?;
+    println!(
+        "watching {} and {} (ctrl-c to stop)",
+        src.display(),
+        input_file.display()
+    );
+
+    let mut previous = BTreeMap::new();
+    let mut changed = BTreeSet::new();
+    loop {
+        run_once(root, day, part, input_dir, &changed, &mut previous);
+        changed.clear();
+        // block for the first relevant change, then soak up whatever follows it
+        while changed.is_empty() {
+            let event = rx.recv().map_err(io::Error::other)?;
+            changed.extend(relevant_paths(event, &src, &input_file));
+        }
+        while let Ok(event) = rx.recv_timeout(SETTLE) {
+            changed.extend(relevant_paths(event, &src, &input_file));
+        }
+    }
+}
+
+fn relevant_paths(
+    event: notify::Result<notify::Event>,
+    src: &Path,
+    input_file: &Path,
+) -> Vec<PathBuf> {
+    match event {
+        // reads happen all the time, not least from our own builds
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => event
+            .paths
+            .into_iter()
+            .filter(|path| path.starts_with(src) || path == input_file)
+            .collect(),
+        Ok(_) => vec![],
+        Err(error) => {
+            eprintln!("watch error: {}", error);
+            vec![]
+        }
+    }
+}
+
+fn cargo(root: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+    command.current_dir(root).args(args);
+    command
+}
+
+/// Builds, runs and tests the day once, reporting answers that differ from `previous`.
+fn run_once(
+    root: &Path,
+    day: u8,
+    part: &str,
+    input_dir: &Path,
+    changed: &BTreeSet<PathBuf>,
+    previous: &mut BTreeMap<u64, String>,
+) {
+    println!("\n---");
+    for path in changed {
+        println!("changed: {}", path.display());
+    }
+
+    // build errors go straight to the terminal, then we wait for the next change
+    let built = cargo(root, &["build", "-q", "-p", "runner"]).status();
+    if !matches!(built, Ok(status) if status.success()) {
+        println!("build failed; waiting for changes");
+        return;
+    }
+
+    let day_arg = day.to_string();
+    let input_dir_arg = input_dir.display().to_string();
+    let run = cargo(
+        root,
+        &[
+            "run",
+            "-q",
+            "-p",
+            "runner",
+            "--",
+            "--day",
+            &day_arg,
+            "--part",
+            part,
+            "--input-dir",
+            &input_dir_arg,
+            "--format",
+            "json",
+        ],
+    )
+    .stderr(Stdio::inherit())
+    .output();
+    match run
+        .ok()
+        .and_then(|output| serde_json::from_slice::<Vec<Value>>(&output.stdout).ok())
+    {
+        Some(records) => {
+            for record in records {
+                print_record(day, &record, previous);
+            }
+        }
+        None => println!("day {}: the runner didn't report any answers", day),
+    }
+
+    let package = format!("day{}", day);
+    match cargo(root, &["test", "-q", "-p", &package]).output() {
+        Ok(output) if output.status.success() => println!("sample tests: passed"),
+        Ok(output) => {
+            println!("sample tests: FAILED");
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        }
+        Err(error) => println!("sample tests: couldn't run cargo test: {}", error),
+    }
+}
+
+fn print_record(day: u8, record: &Value, previous: &mut BTreeMap<u64, String>) {
+    let part = record["part"].as_u64().unwrap_or_default();
+    let answer = match &record["answer"] {
+        Value::String(text) => text.clone(),
+        Value::Null => match record["status"].as_str() {
+            Some("unsolved") => "not yet solved".to_string(),
+            _ => format!(
+                "error: {}",
+                record["error"].as_str().unwrap_or("unknown error")
+            ),
+        },
+        number => number.to_string(),
+    };
+    let timing = record["duration_ns"]
+        .as_u64()
+        .map(|ns| format!(" ({:.2?})", Duration::from_nanos(ns)))
+        .unwrap_or_default();
+    let was = match previous.insert(part, answer.clone()) {
+        Some(before) if before != answer => format!("  [was {}]", before),
+        _ => String::new(),
+    };
+    println!("day {}, part {}: {}{}{}", day, part, answer, timing, was);
+}